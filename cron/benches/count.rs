@@ -0,0 +1,31 @@
+use chrono::{TimeZone, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use cron::schedule::Schedule;
+
+/// The same schedules as the `next_occurrence` bench,
+/// from ones that run every second to ones that run
+/// a few times a decade.
+const SCHEDULES: [(&str, &str); 6] = [
+    ("every second", "* * * * * *"),
+    ("every 5 seconds", "*/5 * * * * *"),
+    ("weekday office hours", "0 */15 9-17 * * Mon-Fri"),
+    ("leap day", "0 0 12 29 2 *"),
+    ("last Friday", "0 0 9 ? * 5L"),
+    ("fifth Friday", "0 0 0 ? * Fri#5"),
+];
+
+fn count_a_year(c: &mut Criterion) {
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut group = c.benchmark_group("count");
+    for (name, expression) in SCHEDULES {
+        let schedule: Schedule = expression.parse().unwrap();
+        group.bench_function(format!("{name}, a year"), |b| {
+            b.iter(|| schedule.count_between(black_box(&start), black_box(&end), Utc))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, count_a_year);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+//! Times how long it takes to find the next datetimes of a
+//! schedule, for ones that run often and ones that run rarely.
+//!
+//! The fields used to be searched with ring buffers, before
+//! they were bitmasks. To compare the two, save a baseline from
+//! the commit before the switch, with this bench copied in, and
+//! then run the bench here against it:
+//!
+//! ```text
+//! repo=$(git rev-parse --show-toplevel)
+//! git worktree add /tmp/rings 3392eb4^
+//! git -C /tmp/rings checkout 3392eb4 -- cron/Cargo.toml cron/benches
+//! cp "$repo/cron/benches/next_occurrence.rs" /tmp/rings/cron/benches/
+//! cd /tmp/rings/cron
+//! CARGO_TARGET_DIR="$repo/target" cargo bench --bench next_occurrence -- --save-baseline rings
+//! cd "$repo/cron"
+//! cargo bench --bench next_occurrence -- --baseline rings
+//! ```
+//!
+//! The bitmasks are a lot faster on the sparse schedules, where
+//! most of the work is skipping over days and months. They aren't
+//! on the dense ones: weekday office hours got slower with them,
+//! and single lookups on dense schedules have gotten slower again
+//! since, from the timezone and daylight saving handling around
+//! the fields.
+
+use chrono::{TimeZone, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use cron::schedule::Schedule;
+
+/// Schedules that run often, where finding the next
+/// datetime barely has to look past the current one.
+const DENSE: [(&str, &str); 3] = [
+    ("every second", "* * * * * *"),
+    ("every 5 seconds", "*/5 * * * * *"),
+    ("weekday office hours", "0 */15 9-17 * * Mon-Fri"),
+];
+
+/// Schedules that run rarely, where most of the work
+/// is skipping over days and months that don't match.
+const SPARSE: [(&str, &str); 3] = [
+    ("leap day", "0 0 12 29 2 *"),
+    ("last Friday", "0 0 9 ? * 5L"),
+    ("fifth Friday", "0 0 0 ? * Fri#5"),
+];
+
+fn next_occurrence(c: &mut Criterion) {
+    let start = Utc.with_ymd_and_hms(2023, 6, 5, 13, 16, 0).unwrap();
+    for (group, schedules) in [("dense", DENSE), ("sparse", SPARSE)] {
+        let mut group = c.benchmark_group(group);
+        for (name, expression) in schedules {
+            let schedule: Schedule = expression.parse().unwrap();
+            group.bench_function(format!("{name}, next"), |b| {
+                b.iter(|| schedule.after(black_box(&start)))
+            });
+            // Only ten, since a leap day doesn't come around
+            // much more often than that before the last year.
+            group.bench_function(format!("{name}, next 10"), |b| {
+                b.iter(|| schedule.iter_from(black_box(&start)).take(10).count())
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, next_occurrence);
+criterion_main!(benches);
@@ -1,29 +1,394 @@
-#[derive(Debug, Clone, Copy)]
+pub use self::schedule::Schedule;
+use std::{fmt, ops::Range};
+
+/// The error types under the same path as in the `cron`
+/// crate on crates.io, so code written against that
+/// crate can keep its imports. `Error::kind` gives back
+/// an owned `ErrorKind` here, rather than a reference.
+pub mod error {
+    pub use crate::{Error, ErrorKind};
+}
+
+/// Everything that can go wrong while turning
+/// an expression into a `Schedule`.
+///
+/// Errors that point at part of the expression carry
+/// the offending `token` along with its byte `span`,
+/// so that they can be shown right where they happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
+    /// The expression has nothing in it.
     Empty,
-    WrongNumberOfFields,
-    InvalidMacro,
+    /// The expression doesn't have 5, 6 or 7 fields,
+    /// or a macro was followed by more than it takes.
+    WrongNumberOfFields {
+        found: usize,
+    },
+    /// A macro other than `@yearly`, `@annually`, `@monthly`,
+    /// `@weekly`, `@daily`, `@midnight`, `@hourly` or `@every`.
+    InvalidMacro {
+        token: String,
+        span: Range<usize>,
+    },
+    /// An `@every` interval that is missing, zero,
+    /// or not written like `1h30m`.
+    InvalidInterval {
+        token: String,
+        span: Range<usize>,
+    },
+    /// A `CRON_TZ=` prefix naming a timezone that isn't in the
+    /// tz database. Without the `chrono-tz` feature, there
+    /// aren't any timezones to name, so every one ends up here.
+    InvalidTimezone {
+        token: String,
+        span: Range<usize>,
+    },
+    /// Part of an ISO 8601 repeating interval, like
+    /// `R10/2023-06-01T13:16:00-05:00/PT1H`, that can't be
+    /// parsed: the repetitions, the start, or the step.
+    InvalidRepeatingInterval {
+        token: String,
+        span: Range<usize>,
+    },
+    /// A repeating interval that repeats `R0` times,
+    /// so it would never run.
+    NoRepetitions {
+        token: String,
+        span: Range<usize>,
+    },
+    /// An `H` in an expression that was parsed without
+    /// a key to hash, like with `str::parse`. Those have
+    /// to go through `Schedule::parse_with_key` instead.
+    MissingHashKey {
+        token: String,
+        span: Range<usize>,
+    },
+    /// Part of an iCalendar `RRULE` that isn't written the way
+    /// rules are, or a rule without a `FREQ`, which gets an
+    /// empty `token` at the end of the rule.
+    InvalidRule {
+        token: String,
+        span: Range<usize>,
+    },
+    /// Something from another syntax, like systemd's
+    /// `OnCalendar=`, that a `Schedule` has no way to hold.
+    Unsupported {
+        token: String,
+        span: Range<usize>,
+    },
+    /// Part of a field that can't be parsed at all.
+    InvalidField {
+        field: Field,
+        token: String,
+        span: Range<usize>,
+    },
+    /// A value that parsed, but falls outside of
+    /// `min..=max` for its field.
+    OutOfRange {
+        field: Field,
+        token: String,
+        span: Range<usize>,
+        min: u32,
+        max: u32,
+    },
+    /// A value given to `Schedule::builder` that falls
+    /// outside of `min..=max` for its field.
+    ValueOutOfRange {
+        field: Field,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+    /// Values given to `Schedule::builder` that don't go from
+    /// lowest to highest: `value` came right after `previous`.
+    UnsortedValues {
+        field: Field,
+        value: u32,
+        previous: u32,
+    },
+    /// A field that was never given to the `FieldTable`
+    /// builder. Modifiers like `5L` also end up here when
+    /// their part of the day field isn't being used.
+    MissingField(Field),
+    /// A field that was given to the `FieldTable`
+    /// builder without any values in it.
+    EmptyField(Field),
+    /// A field that was given to the `FieldTable`
+    /// builder with values it can't hold.
+    FieldOutOfRange(Field),
+    /// Fields that never line up into a date, like the 30th of
+    /// February, or the 31st of a month with only 30 days. A
+    /// schedule like that would never run.
+    NeverRuns,
     Unknown,
 }
 
+impl Error {
+    /// Moves the span of the error `by` bytes to the right.
+    /// Errors are made with spans relative to the piece of the
+    /// expression that was being parsed, and get shifted on
+    /// their way out until they are relative to the whole thing.
+    pub(crate) fn shifted(mut self, by: usize) -> Self {
+        match self {
+            Error::InvalidMacro { ref mut span, .. }
+            | Error::InvalidInterval { ref mut span, .. }
+            | Error::InvalidTimezone { ref mut span, .. }
+            | Error::InvalidRepeatingInterval { ref mut span, .. }
+            | Error::NoRepetitions { ref mut span, .. }
+            | Error::MissingHashKey { ref mut span, .. }
+            | Error::InvalidRule { ref mut span, .. }
+            | Error::Unsupported { ref mut span, .. }
+            | Error::InvalidField { ref mut span, .. }
+            | Error::OutOfRange { ref mut span, .. } => {
+                *span = span.start + by..span.end + by;
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Returns the byte span of the expression that
+    /// caused the error, if there is one.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Error::InvalidMacro { span, .. }
+            | Error::InvalidInterval { span, .. }
+            | Error::InvalidTimezone { span, .. }
+            | Error::InvalidRepeatingInterval { span, .. }
+            | Error::NoRepetitions { span, .. }
+            | Error::MissingHashKey { span, .. }
+            | Error::InvalidRule { span, .. }
+            | Error::Unsupported { span, .. }
+            | Error::InvalidField { span, .. }
+            | Error::OutOfRange { span, .. } => Some(span.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Empty => write!(f, "the expression is empty"),
+            Error::WrongNumberOfFields { found } => write!(
+                f,
+                "expected 5, 6 or 7 fields, or a single macro, but found {found}"
+            ),
+            Error::InvalidMacro { token, span } => {
+                write!(f, "unknown macro `{token}` at column {}", span.start + 1)
+            }
+            Error::InvalidInterval { token, span } if token.is_empty() => {
+                write!(f, "missing interval at column {}", span.start + 1)
+            }
+            Error::InvalidInterval { token, span } => {
+                write!(f, "invalid interval `{token}` at column {}", span.start + 1)
+            }
+            Error::InvalidTimezone { token, span } => {
+                write!(f, "unknown timezone `{token}` at column {}", span.start + 1)
+            }
+            Error::InvalidRepeatingInterval { token, span } => write!(
+                f,
+                "invalid repeating interval `{token}` at column {}",
+                span.start + 1
+            ),
+            Error::NoRepetitions { token, span } => write!(
+                f,
+                "`{token}` at column {} repeats zero times, so the schedule never runs",
+                span.start + 1
+            ),
+            Error::MissingHashKey { token, span } => write!(
+                f,
+                "`{token}` at column {} needs a key to hash",
+                span.start + 1
+            ),
+            Error::InvalidRule { token, span } if token.is_empty() => {
+                write!(f, "missing FREQ at column {}", span.start + 1)
+            }
+            Error::InvalidRule { token, span } => {
+                write!(f, "invalid rule part `{token}` at column {}", span.start + 1)
+            }
+            Error::Unsupported { token, span } => {
+                write!(f, "`{token}` at column {} isn't supported", span.start + 1)
+            }
+            Error::InvalidField { field, token, span } => {
+                write!(f, "invalid {field} `{token}` at column {}", span.start + 1)
+            }
+            Error::OutOfRange {
+                field,
+                token,
+                span,
+                min,
+                max,
+            } => write!(
+                f,
+                "{field} {token} out of range {min}-{max} at column {}",
+                span.start + 1
+            ),
+            Error::ValueOutOfRange {
+                field,
+                value,
+                min,
+                max,
+            } => write!(f, "{field} {value} out of range {min}-{max}"),
+            Error::UnsortedValues {
+                field,
+                value,
+                previous,
+            } => write!(
+                f,
+                "{field} {value} comes after {previous}, but values have to go from lowest to highest"
+            ),
+            Error::MissingField(field) => write!(f, "missing the {field} field"),
+            Error::EmptyField(field) => write!(f, "the {field} field is empty"),
+            Error::FieldOutOfRange(field) => {
+                write!(f, "the {field} field has values that are out of range")
+            }
+            Error::NeverRuns => write!(
+                f,
+                "the schedule never runs, since none of its days land in its months and years"
+            ),
+            Error::Unknown => write!(f, "unknown error"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The kinds of errors that the `cron` crate on crates.io
+/// has, for code that was written to match on them.
+/// Every error here is a problem with the expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    Expression(String),
+}
+
+impl Error {
+    /// Returns the error as one of the kinds that the `cron`
+    /// crate on crates.io has, with the message that would
+    /// be shown by `Display`.
+    ///
+    /// The kind gets built when it's asked for, so unlike
+    /// there it comes back owned instead of as `&ErrorKind`.
+    /// Matching on `error.kind()` works the same either way.
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::Expression(self.to_string())
+    }
+}
+
+/// The fields of a cron expression,
+/// in the order that they are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Seconds,
+    Minutes,
+    Hours,
+    DaysOfMonth,
+    Months,
+    DaysOfWeek,
+    Years,
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Field::Seconds => "second",
+            Field::Minutes => "minute",
+            Field::Hours => "hour",
+            Field::DaysOfMonth => "day of the month",
+            Field::Months => "month",
+            Field::DaysOfWeek => "day of the week",
+            Field::Years => "year",
+        })
+    }
+}
+
 pub mod schedule {
+    pub use self::{
+        builder::{ScheduleBuilder, Values},
+        calendar::{Calendar, CalendarError},
+        dst::{AmbiguousTime, DstPolicy, NonexistentTime},
+        set::{Occurrences, ScheduleSet},
+    };
     use self::{
         fields::FieldTable,
+        interval::Interval,
         iterator::{OwnedScheduleIter, ScheduleIter},
+        repeating::Repeating,
     };
     use crate::Error;
-    use chrono::{DateTime, Datelike, TimeZone, Utc};
-    use std::str::FromStr;
+    use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+    use std::{fmt, str::FromStr};
 
+    mod builder;
+    mod calendar;
+    mod count;
+    mod dst;
     mod fields;
+    mod interval;
     mod iterator;
+    mod on_calendar;
+    mod parser;
+    mod repeating;
+    mod rrule;
+    mod set;
 
     #[derive(Clone, Debug)]
     pub struct Schedule {
-        fields: Box<FieldTable>,
+        timing: Timing,
+        dst: DstPolicy,
+        /// The timezone from a `CRON_TZ=` prefix. When there is
+        /// one, the fields get read in it, no matter which
+        /// timezone the datetimes are asked for in.
+        #[cfg(feature = "chrono-tz")]
+        timezone: Option<chrono_tz::Tz>,
+    }
+
+    /// What decides when a schedule fires: the fields of a
+    /// cron expression, a fixed interval, or a fixed interval
+    /// that counts from a start of its own.
+    #[derive(Clone, Debug)]
+    enum Timing {
+        Fields(Box<FieldTable>),
+        Every(Interval),
+        Repeating(Repeating),
     }
 
     impl Schedule {
+        fn new(timing: Timing) -> Self {
+            Schedule {
+                timing,
+                dst: DstPolicy::default(),
+                #[cfg(feature = "chrono-tz")]
+                timezone: None,
+            }
+        }
+
+        /// Returns true if the schedule runs at least once, ever.
+        /// Expressions and `Schedule::builder` already turn down
+        /// schedules that never run with `Error::NeverRuns`, so
+        /// this is a way for code that's handed a schedule, like
+        /// one loaded from a config at runtime, to double-check it
+        /// without caring how it was made.
+        ///
+        /// A schedule whose last year has already gone by did run,
+        /// back then, so it still counts as satisfiable.
+        pub fn is_satisfiable(&self) -> bool {
+            match self.timing {
+                Timing::Fields(ref fields) => fields.is_satisfiable(),
+                Timing::Every(_) | Timing::Repeating(_) => true,
+            }
+        }
+
+        /// Starts building a schedule out of typed values,
+        /// rather than parsing one out of an expression.
+        /// See `ScheduleBuilder` for how the fields work.
+        pub fn builder() -> ScheduleBuilder {
+            ScheduleBuilder::default()
+        }
+
+        /// Iterates over the datetimes that come after now, in `tz`.
+        /// With a `CRON_TZ=` prefix the fields still get read in
+        /// that timezone, and only the results are put into `tz`.
         pub fn iter_with_timezone<Tz: TimeZone + Clone + 'static>(
             &mut self,
             tz: Tz,
@@ -32,72 +397,661 @@ pub mod schedule {
             ScheduleIter::new(self, first)
         }
 
-        fn recalibrate<Tz: TimeZone + Clone + 'static>(&mut self, tz: &Tz) -> Option<DateTime<Tz>> {
-            let first = self.fields.after(&Utc::now().with_timezone(tz));
-            first.and_then(|dt| dt.and_local_timezone(tz.clone()).earliest())
+        fn recalibrate<Tz: TimeZone + Clone + 'static>(&self, tz: &Tz) -> Option<DateTime<Tz>> {
+            self.after(&Utc::now().with_timezone(tz))
         }
 
         pub fn into_iter_with_timezone<Tz: TimeZone + 'static>(
-            mut self,
+            self,
             tz: Tz,
         ) -> impl Iterator<Item = DateTime<Tz>> {
             let first = self.recalibrate(&tz);
             OwnedScheduleIter::new(self, first)
         }
 
-        fn next<Tz: TimeZone + 'static>(
-            &mut self,
+        /// Iterates over the datetimes that come after now, in
+        /// `tz`. The same as `Schedule::upcoming` in the `cron`
+        /// crate on crates.io, whose `after(&datetime)` is
+        /// `iter_from` here. Like `iter_with_timezone`, it reads
+        /// the fields in the `CRON_TZ=` timezone when there is one.
+        pub fn upcoming<Tz: TimeZone + 'static>(
+            &self,
+            tz: Tz,
+        ) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+            ScheduleIter::new(self, self.recalibrate(&tz))
+        }
+
+        /// The owned version of `upcoming`, which holds on
+        /// to a copy of the schedule so that the iterator
+        /// can outlive it.
+        pub fn upcoming_owned<Tz: TimeZone + 'static>(
+            &self,
+            tz: Tz,
+        ) -> impl Iterator<Item = DateTime<Tz>> {
+            self.clone().into_iter_with_timezone(tz)
+        }
+
+        /// Returns the timezone from the expression's
+        /// `CRON_TZ=` prefix, if it had one.
+        #[cfg(feature = "chrono-tz")]
+        pub fn timezone(&self) -> Option<chrono_tz::Tz> {
+            self.timezone
+        }
+
+        /// Reads the fields in `timezone` from now on, the same
+        /// as starting the expression with `CRON_TZ=`. Datetimes
+        /// still come out in whichever timezone they're asked for.
+        #[cfg(feature = "chrono-tz")]
+        pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+            self.timezone = Some(timezone);
+            self
+        }
+
+        /// Returns true if the schedule runs at `datetime`, checked
+        /// straight against the fields of the expression. Anything
+        /// under a second is ignored, so any moment during the
+        /// second that the schedule runs at counts.
+        ///
+        /// An `@every` schedule doesn't line up with the clock,
+        /// so it never matches anything. A repeating interval
+        /// matches the seconds that it runs in.
+        pub fn matches<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> bool {
+            #[cfg(feature = "chrono-tz")]
+            if let Some(timezone) = self.timezone {
+                return self.matches_in(&datetime.with_timezone(&timezone));
+            }
+            self.matches_in(datetime)
+        }
+
+        /// `matches`, reading the fields in the
+        /// timezone of `datetime`.
+        fn matches_in<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> bool {
+            match self.timing {
+                Timing::Fields(ref fields) => self.dst.matches(
+                    datetime,
+                    |local| fields.contains(local),
+                    |local| fields.after(local),
+                ),
+                Timing::Every(_) => false,
+                Timing::Repeating(ref repeating) => repeating.contains(datetime),
+            }
+        }
+
+        /// Returns true if the fields of the expression allow
+        /// the local time `datetime`, without a timezone and so
+        /// without the `DstPolicy` getting a say. Schedules
+        /// without fields never match a local time.
+        pub fn matches_local(&self, datetime: &NaiveDateTime) -> bool {
+            match self.timing {
+                Timing::Fields(ref fields) => fields.contains(datetime),
+                Timing::Every(_) | Timing::Repeating(_) => false,
+            }
+        }
+
+        /// Returns true if the two schedules run at exactly the same
+        /// times, however they were written, so `*/15` is the same
+        /// as `0,15,30,45`, and `Mon-Fri` is the same as `1-5`.
+        ///
+        /// This compares the parsed fields rather than trying out
+        /// datetimes, so it's exact. See `is_subset_of` for what
+        /// does and doesn't get compared.
+        pub fn equivalent(&self, other: &Schedule) -> bool {
+            self.is_subset_of(other) && other.is_subset_of(self)
+        }
+
+        /// Returns true if every time this schedule runs, `other`
+        /// runs too, like `0 0 9 * * Mon` inside of `0 0 9 * * *`.
+        ///
+        /// Schedules with fields get compared on the local times
+        /// they run at, and a timezone from `CRON_TZ=` has to be
+        /// the same for both. The `DstPolicy` isn't compared,
+        /// since it only changes anything around a clock change.
+        /// An `@every` schedule counts from whenever it's started,
+        /// so it's only compared with other `@every` schedules,
+        /// as if both were started at the same time, and repeating
+        /// intervals only get compared with each other too.
+        pub fn is_subset_of(&self, other: &Schedule) -> bool {
+            match (&self.timing, &other.timing) {
+                (Timing::Fields(fields), Timing::Fields(others)) => {
+                    #[cfg(feature = "chrono-tz")]
+                    if self.timezone != other.timezone {
+                        return false;
+                    }
+                    fields.is_subset_of(others)
+                }
+                (Timing::Every(interval), Timing::Every(others)) => interval.is_subset_of(others),
+                (Timing::Repeating(repeating), Timing::Repeating(others)) => {
+                    repeating.is_subset_of(others)
+                }
+                _ => false,
+            }
+        }
+
+        /// Describes the schedule in English, like "At 13:16:00,
+        /// 14:16:00 and 15:16:00, Monday through Friday". This is
+        /// built from the parsed fields, so it doesn't depend on how
+        /// the expression was written, and it's meant for people
+        /// to read rather than for parsing back.
+        pub fn describe(&self) -> String {
+            let description = match self.timing {
+                Timing::Fields(ref fields) => fields.describe(),
+                Timing::Every(ref interval) => interval.describe(),
+                Timing::Repeating(ref repeating) => repeating.describe(),
+            };
+            #[cfg(feature = "chrono-tz")]
+            if let (Some(timezone), Timing::Fields(_)) = (self.timezone, &self.timing) {
+                return format!("{description}, {} time", timezone.name());
+            }
+            description
+        }
+
+        /// Wraps the schedule in a `ScheduleSet` that
+        /// skips every day in `calendar`.
+        pub fn with_calendar(self, calendar: &Calendar) -> ScheduleSet {
+            ScheduleSet::from(self).except_calendar(calendar)
+        }
+
+        /// Sets what the schedule does with local times that
+        /// get skipped or repeated when the clocks change.
+        /// `@every` schedules count in absolute time, so
+        /// they aren't affected by the policy.
+        pub fn with_dst_policy(mut self, dst: DstPolicy) -> Self {
+            self.dst = dst;
+            self
+        }
+
+        /// Iterates over the datetimes that come strictly
+        /// after `start`, in the same timezone as `start`.
+        ///
+        /// Unlike `iter_with_timezone`, this doesn't depend on
+        /// the current time, so the same `start` always gives
+        /// back the same datetimes.
+        pub fn iter_from<Tz: TimeZone + 'static>(
+            &self,
+            start: &DateTime<Tz>,
+        ) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+            ScheduleIter::new(self, self.after(start))
+        }
+
+        /// The owned version of `iter_from`.
+        pub fn into_iter_from<Tz: TimeZone + 'static>(
+            self,
+            start: &DateTime<Tz>,
+        ) -> impl Iterator<Item = DateTime<Tz>> {
+            let first = self.after(start);
+            OwnedScheduleIter::new(self, first)
+        }
+
+        /// Iterates over the datetimes that come strictly before
+        /// `start`, walking back into the past from the most recent
+        /// one, in the same timezone as `start`.
+        ///
+        /// Past occurrences between two datetimes can be
+        /// found by stopping with `take_while`.
+        pub fn iter_backwards_from<Tz: TimeZone + 'static>(
+            &self,
+            start: &DateTime<Tz>,
+        ) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+            ScheduleIter::new_backwards(self, self.before(start))
+        }
+
+        /// The owned version of `iter_backwards_from`.
+        pub fn into_iter_backwards_from<Tz: TimeZone + 'static>(
+            self,
+            start: &DateTime<Tz>,
+        ) -> impl Iterator<Item = DateTime<Tz>> {
+            let first = self.before(start);
+            OwnedScheduleIter::new_backwards(self, first)
+        }
+
+        /// Returns the first datetime that the schedule fires
+        /// at, strictly after `datetime`, or `None` if the
+        /// schedule never fires again.
+        ///
+        /// In the `cron` crate on crates.io, `after` returns an
+        /// iterator instead. `iter_from` takes the same argument and
+        /// gives the same datetimes, so code written against that
+        /// crate's `schedule.after(&datetime).take(n)` can use
+        /// `schedule.iter_from(&datetime).take(n)` here.
+        pub fn after<Tz: TimeZone + 'static>(
+            &self,
+            datetime: &DateTime<Tz>,
+        ) -> Option<DateTime<Tz>> {
+            #[cfg(feature = "chrono-tz")]
+            if let Some(timezone) = self.timezone {
+                let next = self.after_in(&datetime.with_timezone(&timezone));
+                return next.map(|next| next.with_timezone(&datetime.timezone()));
+            }
+            self.after_in(datetime)
+        }
+
+        /// `after`, reading the fields in the
+        /// timezone of `datetime`.
+        fn after_in<Tz: TimeZone + 'static>(
+            &self,
+            datetime: &DateTime<Tz>,
+        ) -> Option<DateTime<Tz>> {
+            match self.timing {
+                Timing::Fields(ref fields) => self.dst.after(datetime, |local| fields.after(local)),
+                Timing::Every(ref interval) => interval.after(datetime),
+                Timing::Repeating(ref repeating) => repeating.after(datetime),
+            }
+        }
+
+        /// Returns the last datetime that the schedule fired
+        /// at, strictly before `datetime`, or `None` if the
+        /// schedule never fired before then.
+        ///
+        /// An `@every` schedule has no fixed starting point,
+        /// so this is just `datetime` minus the interval.
+        pub fn before<Tz: TimeZone + 'static>(
+            &self,
+            datetime: &DateTime<Tz>,
+        ) -> Option<DateTime<Tz>> {
+            #[cfg(feature = "chrono-tz")]
+            if let Some(timezone) = self.timezone {
+                let prev = self.before_in(&datetime.with_timezone(&timezone));
+                return prev.map(|prev| prev.with_timezone(&datetime.timezone()));
+            }
+            self.before_in(datetime)
+        }
+
+        /// `before`, reading the fields in the
+        /// timezone of `datetime`.
+        fn before_in<Tz: TimeZone + 'static>(
+            &self,
             datetime: &DateTime<Tz>,
         ) -> Option<DateTime<Tz>> {
-            let month = datetime.month() as u8;
-            let year = datetime.year() as u32;
-            self.fields
-                .next(month, year)
-                .and_then(|dt| dt.and_local_timezone(datetime.timezone()).earliest())
+            match self.timing {
+                Timing::Fields(ref fields) => {
+                    self.dst.before(datetime, |local| fields.before(local))
+                }
+                Timing::Every(ref interval) => interval.before(datetime),
+                Timing::Repeating(ref repeating) => repeating.before(datetime),
+            }
+        }
+
+        /// Returns how many times the schedule runs strictly after
+        /// `start`, up to and including `end`, in `tz`. That's the
+        /// same as counting `iter_from(start)` until it passes `end`.
+        /// A timezone from a `CRON_TZ=` prefix takes the place of `tz`.
+        ///
+        /// Instead of going through the datetimes one by one, this
+        /// multiplies out how many values each field allows, so a
+        /// schedule that runs every second can be counted over a
+        /// year about as quickly as one that runs once a day.
+        pub fn count_between<Tz: TimeZone + 'static, Other: TimeZone>(
+            &self,
+            start: &DateTime<Other>,
+            end: &DateTime<Other>,
+            tz: Tz,
+        ) -> u64 {
+            #[cfg(feature = "chrono-tz")]
+            if let Some(timezone) = self.timezone {
+                return self.count_in(start, end, timezone);
+            }
+            self.count_in(start, end, tz)
+        }
+
+        /// `count_between`, reading the fields in `tz`.
+        fn count_in<Tz: TimeZone + 'static, Other: TimeZone>(
+            &self,
+            start: &DateTime<Other>,
+            end: &DateTime<Other>,
+            tz: Tz,
+        ) -> u64 {
+            let (start, end) = (start.with_timezone(&tz), end.with_timezone(&tz));
+            match self.timing {
+                Timing::Fields(ref fields) => {
+                    // Everything runs on a whole second, so this turns
+                    // the range into one that starts where it's counted from.
+                    let second = Duration::seconds(1);
+                    let from = count::whole_seconds(&start) + second;
+                    let until = count::whole_seconds(&end) + second;
+                    count::count(self, fields, &from, &until)
+                }
+                Timing::Every(ref interval) => interval.count_between(&start, &end),
+                Timing::Repeating(ref repeating) => repeating.count_between(&start, &end),
+            }
+        }
+
+        /// Returns the `n`th datetime that the schedule runs at
+        /// strictly after `start`, counting from 1, so that
+        /// `nth_after(start, 1)` is the same as `after(start)`.
+        /// Returns `None` for an `n` of 0, or if the schedule
+        /// stops before getting that far.
+        ///
+        /// Like `count_between`, this counts whole days and months
+        /// at a time rather than stepping through every datetime.
+        pub fn nth_after<Tz: TimeZone + 'static>(
+            &self,
+            start: &DateTime<Tz>,
+            n: u64,
+        ) -> Option<DateTime<Tz>> {
+            #[cfg(feature = "chrono-tz")]
+            if let Some(timezone) = self.timezone {
+                let nth = self.nth_after_in(&start.with_timezone(&timezone), n);
+                return nth.map(|nth| nth.with_timezone(&start.timezone()));
+            }
+            self.nth_after_in(start, n)
+        }
+
+        /// `nth_after`, reading the fields in
+        /// the timezone of `start`.
+        fn nth_after_in<Tz: TimeZone + 'static>(
+            &self,
+            start: &DateTime<Tz>,
+            n: u64,
+        ) -> Option<DateTime<Tz>> {
+            match self.timing {
+                Timing::Fields(ref fields) => {
+                    let from = count::whole_seconds(start) + Duration::seconds(1);
+                    count::nth(self, fields, &from, n)
+                }
+                Timing::Every(_) if n == 0 => None,
+                Timing::Every(ref interval) => interval.nth_after(start, n),
+                Timing::Repeating(ref repeating) => repeating.nth_after(start, n),
+            }
+        }
+
+        /// Parses an expression that can use `H` in place of a
+        /// value, like `H H(9-17) * * Mon-Fri` or `H/15 * * * *`.
+        /// Each `H` becomes a value picked by hashing `key`, which
+        /// is usually the name of the job.
+        ///
+        /// That spreads out jobs that would otherwise all run at
+        /// the top of the hour, while the same key always gets the
+        /// same values, no matter when or where it's parsed. The
+        /// picked values are what `Display` writes back out. The
+        /// year can't be hashed, since the year picked would often
+        /// have already gone by.
+        ///
+        /// ```
+        /// # use cron::Schedule;
+        /// let backup = Schedule::parse_with_key("0 H H(1-5) * * *", "backup").unwrap();
+        /// let again = Schedule::parse_with_key("0 H H(1-5) * * *", "backup").unwrap();
+        /// assert_eq!(backup.to_string(), again.to_string());
+        /// ```
+        pub fn parse_with_key(expression: &str, key: &str) -> Result<Self, Error> {
+            Schedule::from_expression(expression, Some(key))
+        }
+
+        /// Parses a calendar event in systemd's `OnCalendar=`
+        /// syntax, like `Mon..Fri *-*-* 13..18:16:00`, so that
+        /// timers can move over without being rewritten by hand.
+        /// The shorthands like `daily` and `weekly` work too.
+        ///
+        /// A timezone at the end, like `Europe/Berlin`, needs the
+        /// `chrono-tz` feature, and works like `CRON_TZ=` does.
+        ///
+        /// systemd only runs on days that match both the weekdays
+        /// and the date, which a schedule can't always do, so a
+        /// list of weekdays along with particular days of the
+        /// month fails with an `Error::Unsupported`.
+        ///
+        /// ```
+        /// # use cron::Schedule;
+        /// let schedule = Schedule::from_on_calendar("Mon..Fri *-*-* 13..18:16:00").unwrap();
+        /// assert_eq!("0 16 13-18 * * Mon-Fri", schedule.to_string());
+        /// ```
+        pub fn from_on_calendar(expression: &str) -> Result<Self, Error> {
+            let (fields, timezone) = on_calendar::parse(expression)?;
+            let Some((at, name)) = timezone else {
+                return Ok(Schedule::from(fields));
+            };
+            #[cfg(feature = "chrono-tz")]
+            if let Ok(timezone) = name.parse() {
+                return Ok(Schedule::from(fields).with_timezone(timezone));
+            }
+            Err(Error::InvalidTimezone {
+                token: name.to_owned(),
+                span: at..at + name.len(),
+            })
+        }
+
+        /// Parses an ISO 8601 repeating interval, like
+        /// `R10/2023-06-01T13:16:00-05:00/PT1H`, which runs 10
+        /// times, an hour apart, starting at 13:16 in UTC-5. With
+        /// nothing after the `R`, like `R/2023-06-01T00:00:00Z/P1D`,
+        /// it keeps going forever.
+        ///
+        /// Unlike `@every`, the times count from the start that's
+        /// written down, so they're the same wherever and whenever
+        /// the schedule is used. The step can be written like
+        /// `P1DT12H` or `P2W`, or the end of the first step can
+        /// take its place, like in `R/2023-06-01T09:00Z/2023-06-01T10:30Z`.
+        /// See `Display` for how it gets written back out. Parsing
+        /// the schedule as an expression works for these too.
+        ///
+        /// Days are always 24 hours long, since the start carries
+        /// a fixed UTC offset, while months and years go by the
+        /// calendar. Repeating `R0` times fails with an
+        /// `Error::NoRepetitions`.
+        ///
+        /// ```
+        /// # use chrono::{TimeZone, Utc};
+        /// # use cron::Schedule;
+        /// let schedule = Schedule::from_repeating_interval("R3/2023-06-01T13:16:00-05:00/PT1H").unwrap();
+        /// let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        /// let times: Vec<_> = schedule.iter_from(&start).map(|time| time.to_rfc3339()).collect();
+        /// assert_eq!(
+        ///     vec![
+        ///         "2023-06-01T18:16:00+00:00",
+        ///         "2023-06-01T19:16:00+00:00",
+        ///         "2023-06-01T20:16:00+00:00",
+        ///     ],
+        ///     times
+        /// );
+        /// ```
+        pub fn from_repeating_interval(interval: &str) -> Result<Self, Error> {
+            let repeating = interval.parse()?;
+            Ok(Schedule::new(Timing::Repeating(repeating)))
+        }
+
+        /// Parses an iCalendar (RFC 5545) recurrence rule, like
+        /// `FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=13;BYMINUTE=16`.
+        /// The rule can be the whole `RRULE:` line from a calendar.
+        ///
+        /// A rule fills in what it leaves out from the start of its
+        /// event, and there isn't one here, so anything finer than
+        /// `FREQ` starts at its lowest value: a `DAILY` rule runs at
+        /// midnight, and a `MONTHLY` one on the 1st. An `INTERVAL`
+        /// only works when it goes evenly into the field that `FREQ`
+        /// steps through, like every 15 minutes or every 6 hours.
+        ///
+        /// Parts that a schedule can't follow, like `COUNT`, `UNTIL`
+        /// or `BYSETPOS`, fail with an `Error::Unsupported`, and so
+        /// does a `BYDAY` along with a `BYMONTHDAY`, since a rule
+        /// only runs on the days that match both.
+        ///
+        /// ```
+        /// # use cron::Schedule;
+        /// let rule = "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=13,14,15,16,17,18;BYMINUTE=16";
+        /// let schedule = Schedule::from_rrule(rule).unwrap();
+        /// assert_eq!("0 16 13-18 * * Mon-Fri", schedule.to_string());
+        /// ```
+        pub fn from_rrule(rule: &str) -> Result<Self, Error> {
+            rrule::parse(rule).map(Schedule::from)
+        }
+
+        /// Writes the schedule as the value of an `RRULE`, in the
+        /// local time that its fields are read in, or returns `None`
+        /// if a rule can't say the same thing. That's the case for
+        /// `@every` schedules and repeating intervals, restricted
+        /// years, `W` days, and
+        /// schedules with both days of the month and of the week.
+        pub fn to_rrule(&self) -> Option<String> {
+            match self.timing {
+                Timing::Fields(ref fields) => fields.to_rrule(),
+                Timing::Every(_) | Timing::Repeating(_) => None,
+            }
+        }
+
+        /// Writes the next `count` times that the schedule runs,
+        /// strictly after `start`, as an iCalendar (`.ics`) file
+        /// with a single event, so that they can be looked over
+        /// in a calendar app.
+        ///
+        /// The times are written in UTC. When the schedule runs in
+        /// UTC and `to_rrule` has a rule for it, the event repeats
+        /// with that rule, and otherwise every time is listed out,
+        /// so that the calendar doesn't drift across clock changes.
+        pub fn to_ics<Tz: TimeZone + 'static>(&self, start: &DateTime<Tz>, count: usize) -> String {
+            // The rule is in the local time that the fields are read
+            // in, so the offsets have to be checked in that timezone.
+            #[cfg(feature = "chrono-tz")]
+            if let Some(timezone) = self.timezone {
+                let start = start.with_timezone(&timezone);
+                let datetimes: Vec<_> = self.iter_from(&start).take(count).collect();
+                return rrule::ics(&datetimes, self.to_rrule(), &self.describe());
+            }
+            let datetimes: Vec<_> = self.iter_from(start).take(count).collect();
+            rrule::ics(&datetimes, self.to_rrule(), &self.describe())
+        }
+
+        /// Parses an expression, which can start with a `CRON_TZ=`
+        /// prefix naming the timezone that it runs in.
+        #[cfg(feature = "chrono-tz")]
+        fn from_expression(expression: &str, key: Option<&str>) -> Result<Self, Error> {
+            let mut fields = parser::split(expression);
+            let timezone = parser::timezone(&mut fields)?;
+            let schedule = Schedule::from_fields(&fields, key)?;
+            Ok(Schedule {
+                timezone,
+                ..schedule
+            })
+        }
+
+        /// Parses an expression. Without the `chrono-tz`
+        /// feature, there's no way to give it a timezone.
+        #[cfg(not(feature = "chrono-tz"))]
+        fn from_expression(expression: &str, key: Option<&str>) -> Result<Self, Error> {
+            let mut fields = parser::split(expression);
+            parser::timezone(&mut fields)?;
+            Schedule::from_fields(&fields, key)
+        }
+
+        /// Parses the fields of an expression, a macro,
+        /// or a repeating interval.
+        fn from_fields(fields: &[(usize, &str)], key: Option<&str>) -> Result<Self, Error> {
+            if let [(at, repeating)] = *fields {
+                if repeating.starts_with('R') {
+                    let repeating = repeating
+                        .parse()
+                        .map_err(|error: Error| error.shifted(at))?;
+                    return Ok(Schedule::new(Timing::Repeating(repeating)));
+                }
+            }
+            if fields
+                .first()
+                .is_some_and(|(_, field)| field.starts_with('@'))
+            {
+                return Schedule::from_macro(fields);
+            }
+            match fields.len() {
+                0 => Err(Error::Empty),
+                5 => parser::five_fields(fields, key).map(Schedule::from),
+                6 => parser::six_fields(fields, key).map(Schedule::from),
+                7 => parser::seven_fields(fields, key).map(Schedule::from),
+                found => Err(Error::WrongNumberOfFields { found }),
+            }
+        }
+
+        /// Parses `@every <interval>` and the named macros. The
+        /// names have to match in full, so `@hamburger` is an error
+        /// rather than a funny way of spelling `@hourly`.
+        fn from_macro(fields: &[(usize, &str)]) -> Result<Self, Error> {
+            let fields = match *fields {
+                [(_, "@every"), (at, interval)] => {
+                    let interval = interval.parse().map_err(|error: Error| error.shifted(at))?;
+                    return Ok(Schedule::new(Timing::Every(interval)));
+                }
+                [(at, name @ "@every")] => {
+                    return Err(Error::InvalidInterval {
+                        token: String::new(),
+                        span: at + name.len()..at + name.len(),
+                    })
+                }
+                [_, _, ..] => {
+                    return Err(Error::WrongNumberOfFields {
+                        found: fields.len(),
+                    })
+                }
+                [(_, "@yearly" | "@annually")] => annually(),
+                [(_, "@monthly")] => monthly(),
+                [(_, "@weekly")] => weekly(),
+                [(_, "@daily" | "@midnight")] => daily(),
+                [(_, "@hourly")] => hourly(),
+                [(at, name)] => {
+                    return Err(Error::InvalidMacro {
+                        token: name.to_owned(),
+                        span: at..at + name.len(),
+                    })
+                }
+                [] => return Err(Error::Empty),
+            };
+            Ok(Schedule::from(fields?))
+        }
+    }
+
+    impl From<FieldTable> for Schedule {
+        fn from(fields: FieldTable) -> Self {
+            Schedule::new(Timing::Fields(Box::new(fields)))
         }
     }
 
     impl FromStr for Schedule {
         type Err = Error;
 
+        /// Parses an expression. There's no key to hash here,
+        /// so an `H` is an error; see `Schedule::parse_with_key`.
         fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let fields: Vec<&str> = s.split_whitespace().collect();
-            match fields.len() {
-                0 => Err(Error::Empty),
-                1 => {
-                    let mut maybe_macro = fields[0].chars();
-                    maybe_macro
-                        .next()
-                        .ok_or(Error::Unknown)?
-                        .eq(&'@')
-                        .then(|| match maybe_macro.next().ok_or(Error::InvalidMacro)? {
-                            'y' | 'a' => Ok(Schedule {
-                                fields: Box::new(annually())
-                            }),
-                            'm' => Ok(Schedule {
-                                fields: Box::new(monthly())
-                            }),
-                            'w' => Ok(Schedule {
-                                fields: Box::new(weekly())
-                            }),
-                            'd' => Ok(Schedule {
-                                fields: Box::new(daily())
-                            }),
-                            'h' => Ok(Schedule {
-                                fields: Box::new(hourly())
-                            }),
-                            _ => Err(Error::InvalidMacro),
-                        })
-                        .unwrap_or(Err(Error::WrongNumberOfFields))
-                }
-                5 => unimplemented!("Will eventually be the equivalent of the 6-field version, but with '00' for seconds."),
-                6 => todo!(),
-                _ => Err(Error::WrongNumberOfFields),
+            Schedule::from_expression(s, None)
+        }
+    }
+
+    /// Writes the schedule back out as an expression that
+    /// parses to an equivalent schedule. Values get sorted,
+    /// runs get collapsed into ranges and steps, and names
+    /// are spelled like `Mon` and `Jan`. Macros other than
+    /// `@every` come back out as plain fields, and repeating
+    /// intervals come back out in the extended ISO 8601 format.
+    ///
+    /// The `DstPolicy` isn't part of the expression,
+    /// so it doesn't get written out, but a timezone
+    /// from `CRON_TZ=` does.
+    impl fmt::Display for Schedule {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            #[cfg(feature = "chrono-tz")]
+            if let Some(timezone) = self.timezone {
+                write!(f, "CRON_TZ={} ", timezone.name())?;
             }
+            match self.timing {
+                Timing::Fields(ref fields) => write!(f, "{fields}"),
+                Timing::Every(ref interval) => write!(f, "@every {interval}"),
+                Timing::Repeating(ref repeating) => write!(f, "{repeating}"),
+            }
+        }
+    }
+
+    /// Serializes the schedule as its expression, the same
+    /// way it gets written out by `Display`.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for Schedule {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for Schedule {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let expression = String::deserialize(deserializer)?;
+            expression.parse().map_err(serde::de::Error::custom)
         }
     }
 
-    fn hourly() -> FieldTable {
+    fn hourly() -> Result<FieldTable, fields::Error> {
         FieldTable::builder()
             .with_secs(0)
             .with_mins(0)
@@ -105,10 +1059,9 @@ pub mod schedule {
             .with_days_of_the_month_only_iter(1..=31)
             .with_months_iter(1..=12)
             .build()
-            .unwrap()
     }
 
-    fn daily() -> FieldTable {
+    fn daily() -> Result<FieldTable, fields::Error> {
         FieldTable::builder()
             .with_secs(0)
             .with_mins(0)
@@ -116,10 +1069,9 @@ pub mod schedule {
             .with_days_of_the_month_only_iter(1..=31)
             .with_months_iter(1..=12)
             .build()
-            .unwrap()
     }
 
-    fn weekly() -> FieldTable {
+    fn weekly() -> Result<FieldTable, fields::Error> {
         FieldTable::builder()
             .with_secs(0)
             .with_mins(0)
@@ -127,10 +1079,9 @@ pub mod schedule {
             .with_days_of_the_week_only(0)
             .with_months_iter(1..=12)
             .build()
-            .unwrap()
     }
 
-    fn annually() -> FieldTable {
+    fn annually() -> Result<FieldTable, fields::Error> {
         FieldTable::builder()
             .with_secs(0)
             .with_mins(0)
@@ -138,10 +1089,9 @@ pub mod schedule {
             .with_days_of_the_month_only(1)
             .with_months(1)
             .build()
-            .unwrap()
     }
 
-    fn monthly() -> FieldTable {
+    fn monthly() -> Result<FieldTable, fields::Error> {
         FieldTable::builder()
             .with_secs(0)
             .with_mins(0)
@@ -149,11 +1099,759 @@ pub mod schedule {
             .with_days_of_the_month_only(1)
             .with_months_iter(1..=12)
             .build()
-            .unwrap()
     }
 
     #[cfg(test)]
-    mod tests {}
+    mod tests {
+        use super::Schedule;
+        use crate::Error;
+        use chrono::{Datelike, Duration, TimeZone, Timelike, Utc, Weekday};
+        use chrono_tz::America::New_York;
+
+        #[test]
+        fn iter_follows_the_expression() {
+            let mut schedule: Schedule = "00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri"
+                .parse()
+                .unwrap();
+            let now = Utc::now();
+
+            let mut prev = now;
+            for datetime in schedule.iter_with_timezone(Utc).take(50) {
+                assert!(datetime > prev);
+                assert!((13..=18).contains(&datetime.hour()));
+                assert_eq!(16, datetime.minute());
+                assert_eq!(0, datetime.second());
+                assert!(!matches!(datetime.weekday(), Weekday::Sat | Weekday::Sun));
+                prev = datetime;
+            }
+        }
+
+        #[test]
+        fn iter_ends_after_last_year() {
+            let schedule: Schedule = "0 0 12 1 1 * 2098-2099".parse().unwrap();
+            let datetimes: Vec<_> = schedule.into_iter_with_timezone(Utc).collect();
+
+            assert_eq!(2, datetimes.len());
+            assert_eq!(2098, datetimes[0].year());
+            assert_eq!(2099, datetimes[1].year());
+        }
+
+        #[test]
+        fn upcoming_matches_iter_with_timezone() {
+            let mut schedule: Schedule = "0 16 13 * * Mon-Fri".parse().unwrap();
+
+            let upcoming: Vec<_> = schedule.upcoming(New_York).take(5).collect();
+            let owned: Vec<_> = schedule.upcoming_owned(New_York).take(5).collect();
+            let iter: Vec<_> = schedule.iter_with_timezone(New_York).take(5).collect();
+            assert_eq!(iter, upcoming);
+            assert_eq!(iter, owned);
+            assert!(upcoming[0] > Utc::now());
+        }
+
+        #[test]
+        fn iter_from_stands_in_for_crates_io_after() {
+            let schedule: Schedule = "0 16 13 * * Mon-Fri".parse().unwrap();
+            // Friday, June 9th 2023
+            let start = Utc.with_ymd_and_hms(2023, 6, 9, 12, 0, 0).unwrap();
+
+            assert_eq!(schedule.after(&start), schedule.iter_from(&start).next());
+            assert_eq!(
+                vec![
+                    Utc.with_ymd_and_hms(2023, 6, 9, 13, 16, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2023, 6, 12, 13, 16, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2023, 6, 13, 13, 16, 0).unwrap(),
+                ],
+                schedule.iter_from(&start).take(3).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn errors_have_a_kind() {
+            let error = "0 60 * * * *".parse::<Schedule>().unwrap_err();
+
+            assert_eq!(
+                crate::error::ErrorKind::Expression(error.to_string()),
+                error.kind()
+            );
+        }
+
+        #[test]
+        fn iter_is_empty_once_years_have_passed() {
+            let mut schedule: Schedule = "0 16 13 * * Mon-Fri 2023-2024".parse().unwrap();
+
+            assert!(schedule.iter_with_timezone(Utc).next().is_none());
+        }
+
+        #[test]
+        fn iter_from_is_deterministic() {
+            let schedule: Schedule = "0 0 9 * * Mon-Fri".parse().unwrap();
+            // Friday, June 9th 2023
+            let start = Utc.with_ymd_and_hms(2023, 6, 9, 9, 0, 0).unwrap();
+
+            let datetimes: Vec<_> = schedule.iter_from(&start).take(3).collect();
+            assert_eq!(
+                vec![
+                    Utc.with_ymd_and_hms(2023, 6, 12, 9, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2023, 6, 13, 9, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2023, 6, 14, 9, 0, 0).unwrap(),
+                ],
+                datetimes
+            );
+            let owned: Vec<_> = schedule.into_iter_from(&start).take(3).collect();
+            assert_eq!(datetimes, owned);
+        }
+
+        #[test]
+        fn after_and_before_are_strict() {
+            let schedule: Schedule = "0 30 * * * *".parse().unwrap();
+            let at = Utc.with_ymd_and_hms(2023, 6, 9, 9, 30, 0).unwrap();
+
+            assert_eq!(
+                Some(Utc.with_ymd_and_hms(2023, 6, 9, 10, 30, 0).unwrap()),
+                schedule.after(&at)
+            );
+            assert_eq!(
+                Some(Utc.with_ymd_and_hms(2023, 6, 9, 8, 30, 0).unwrap()),
+                schedule.before(&at)
+            );
+        }
+
+        #[test]
+        fn after_keeps_the_timezone() {
+            let schedule: Schedule = "0 0 9 * * *".parse().unwrap();
+            let start = New_York.with_ymd_and_hms(2023, 6, 9, 12, 0, 0).unwrap();
+
+            let next = schedule.after(&start).unwrap();
+            assert_eq!(New_York, next.timezone());
+            assert_eq!(
+                New_York.with_ymd_and_hms(2023, 6, 10, 9, 0, 0).unwrap(),
+                next
+            );
+        }
+
+        #[test]
+        fn years_before_zero_start_from_the_first_year() {
+            let schedule: Schedule = "0 0 9 * * *".parse().unwrap();
+            let start = Utc.with_ymd_and_hms(-5, 6, 9, 12, 0, 0).unwrap();
+
+            assert_eq!(
+                Some(Utc.with_ymd_and_hms(1970, 1, 1, 9, 0, 0).unwrap()),
+                schedule.after(&start)
+            );
+            assert_eq!(None, schedule.before(&start));
+            assert!(!schedule.matches(&Utc.with_ymd_and_hms(-5, 6, 9, 9, 0, 0).unwrap()));
+        }
+
+        #[test]
+        fn before_ends_before_first_year() {
+            let schedule: Schedule = "0 0 12 1 1 * 2098-2099".parse().unwrap();
+            let at = Utc.with_ymd_and_hms(2098, 1, 1, 12, 0, 0).unwrap();
+
+            assert_eq!(None, schedule.before(&at));
+        }
+
+        #[test]
+        fn iter_backwards_walks_into_the_past() {
+            let schedule: Schedule = "0 0 9 * * Mon-Fri".parse().unwrap();
+            // Tuesday, June 13th 2023
+            let start = Utc.with_ymd_and_hms(2023, 6, 13, 9, 0, 0).unwrap();
+
+            let datetimes: Vec<_> = schedule.iter_backwards_from(&start).take(3).collect();
+            assert_eq!(
+                vec![
+                    Utc.with_ymd_and_hms(2023, 6, 12, 9, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2023, 6, 9, 9, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2023, 6, 8, 9, 0, 0).unwrap(),
+                ],
+                datetimes
+            );
+        }
+
+        #[test]
+        fn iter_backwards_between_two_datetimes() {
+            let schedule: Schedule = "0 0 0 L * *".parse().unwrap();
+            let from = Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap();
+            let until = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+
+            let days: Vec<_> = schedule
+                .iter_backwards_from(&until)
+                .take_while(|datetime| *datetime >= from)
+                .map(|datetime| (datetime.month(), datetime.day()))
+                .collect();
+            assert_eq!(vec![(3, 31), (2, 29), (1, 31), (12, 31)], days);
+        }
+
+        #[test]
+        fn iter_backwards_finds_leap_days() {
+            let schedule: Schedule = "0 0 0 29 2 *".parse().unwrap();
+            let start = Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap();
+
+            let years: Vec<_> = schedule
+                .into_iter_backwards_from(&start)
+                .take(3)
+                .map(|datetime| datetime.year())
+                .collect();
+            assert_eq!(vec![2020, 2016, 2012], years);
+        }
+
+        #[test]
+        fn iter_backwards_ends_before_first_year() {
+            let schedule: Schedule = "0 0 12 1 1 * 2098-2099".parse().unwrap();
+            let start = Utc.with_ymd_and_hms(2100, 1, 1, 0, 0, 0).unwrap();
+
+            assert_eq!(2, schedule.iter_backwards_from(&start).count());
+        }
+
+        #[test]
+        fn describe_reads_like_english() {
+            for (expression, description) in [
+                (
+                    "00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri",
+                    "At 13:16:00, 14:16:00, 15:16:00, 16:16:00, 17:16:00 and 18:16:00, \
+                     Monday through Friday",
+                ),
+                ("* * * * * *", "Every second"),
+                ("*/15 * * * * *", "Every 15 seconds"),
+                ("*/5 * * * *", "Every 5 minutes"),
+                (
+                    "0 */5 9-17 * * *",
+                    "Every 5 minutes, during hours 9 through 17",
+                ),
+                ("0 0,30 * * * *", "At minutes 0 and 30 of every hour"),
+                (
+                    "30 15 * * * *",
+                    "At 15 minutes and 30 seconds past every hour",
+                ),
+                ("@hourly", "Every hour"),
+                ("0 0 */2 * * *", "Every 2 hours"),
+                ("0 9-17 * * *", "Every hour from 09:00:00 through 17:00:00"),
+                (
+                    "0 0 12 L-3,15W,LW * ?",
+                    "At 12:00:00, on the 3rd day before the last day, \
+                     the weekday nearest the 15th and the last weekday of the month",
+                ),
+                (
+                    "0 9 ? * 2#1",
+                    "At 09:00:00, on the first Tuesday of the month",
+                ),
+                (
+                    "0 12 1,15 * Fri",
+                    "At 12:00:00, on the 1st and 15th of the month or on Friday",
+                ),
+                (
+                    "0 10 * Jun-Aug Sat",
+                    "At 10:00:00, only on Saturday, in June through August",
+                ),
+                (
+                    "0 0 0 1 1 * 2023-2025",
+                    "At 00:00:00, on the 1st of the month, in January, in 2023 through 2025",
+                ),
+                ("0 0 0 * */3 *", "At 00:00:00, in every 3rd month"),
+                (
+                    "0 0 0 * Jan,Apr,Jul,Oct *",
+                    "At 00:00:00, in every 3rd month",
+                ),
+                ("0 0 0 * 1-12/2 *", "At 00:00:00, in every 2nd month"),
+                ("0 0 0 * jan/2 *", "At 00:00:00, in every 2nd month"),
+                (
+                    "0 0 0 1 */2 *",
+                    "At 00:00:00, on the 1st of the month, in every 2nd month",
+                ),
+                (
+                    "0 0 0 * Feb/3 *",
+                    "At 00:00:00, in February, May, August and November",
+                ),
+                ("@every 90m", "Every 1 hour and 30 minutes"),
+            ] {
+                let schedule: Schedule = expression.parse().unwrap();
+                assert_eq!(description, schedule.describe(), "{expression:?}");
+            }
+        }
+
+        #[test]
+        fn describe_ignores_how_the_expression_was_written() {
+            let a: Schedule = "0 16 13-18 * * 1-5".parse().unwrap();
+            let b: Schedule = "00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri"
+                .parse()
+                .unwrap();
+            assert_eq!(a.describe(), b.describe());
+        }
+
+        #[test]
+        fn display_normalizes_the_expression() {
+            for (expression, expected) in [
+                (
+                    "00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri",
+                    "0 16 13-18 * * Mon-Fri",
+                ),
+                ("*/15 * * * *", "0 */15 * * * *"),
+                ("0 0 1,2,4,5,6 1-12/3 *", "0 0 0 1,2,4-6 */3 *"),
+                ("0 0 9 ? * mon,WED,fri", "0 0 9 * * Mon-Fri/2"),
+                ("0 0 0 1-31 * 7,1", "0 0 0 1-31 * Sun,Mon"),
+                ("0 0 12 L,15W,1 * *", "0 0 12 1,L,15W * *"),
+                ("0 0 12 ? * 5L,Tue#1", "0 0 12 * * FriL,Tue#1"),
+                ("@daily", "0 0 0 * * *"),
+                ("@weekly", "0 0 0 * * Sun"),
+                ("0 0 12 1 1 * 2098-2099", "0 0 12 1 Jan * 2098,2099"),
+                ("0 0 12 1 1 * *", "0 0 12 1 Jan *"),
+                ("@every 90m", "@every 1h30m"),
+                ("@every 1d3600s", "@every 1d1h"),
+            ] {
+                let schedule: Schedule = expression.parse().unwrap();
+                assert_eq!(expected, schedule.to_string(), "{expression:?}");
+            }
+        }
+
+        #[test]
+        fn display_round_trips() {
+            let start = Utc.with_ymd_and_hms(2023, 6, 9, 9, 0, 0).unwrap();
+            for expression in [
+                "00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri",
+                "0 0 9 1,15 * Mon",
+                "0 0 9 */2 * *",
+                "0 0 9 * * */2",
+                "0 0 9 1-31 * 6-7",
+                "0 0 9 ? * Mon-Fri",
+                "0 0 9 ? * 2-4/2",
+                "0 0 17 LW * ?",
+                "0 0 12 L-3 * Fri#2",
+                "5/20 3-59/7 22 29 Feb *",
+                "0 0 0 * * * 2023-2030/3",
+                "@hourly",
+                "@every 45s",
+            ] {
+                let schedule: Schedule = expression.parse().unwrap();
+                let printed = schedule.to_string();
+                let reparsed: Schedule = printed.parse().unwrap();
+
+                assert_eq!(printed, reparsed.to_string(), "{expression:?}");
+                assert!(
+                    schedule
+                        .iter_from(&start)
+                        .take(100)
+                        .eq(reparsed.iter_from(&start).take(100)),
+                    "{expression:?} printed as {printed:?}"
+                );
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_uses_the_expression() {
+            let schedule: Schedule = "0 0 9 ? * mon-fri".parse().unwrap();
+            let json = serde_json::to_string(&schedule).unwrap();
+            assert_eq!(r#""0 0 9 * * Mon-Fri""#, json);
+
+            let schedule: Schedule = serde_json::from_str(&json).unwrap();
+            assert_eq!("0 0 9 * * Mon-Fri", schedule.to_string());
+
+            let error = serde_json::from_str::<Schedule>(r#""0 0 24 * * *""#).unwrap_err();
+            assert!(error.to_string().contains("hour 24 out of range 0-23"));
+        }
+
+        #[cfg(feature = "chrono-tz")]
+        #[test]
+        fn cron_tz_sets_the_timezone() {
+            use chrono_tz::America::Chicago;
+
+            let schedule: Schedule = "CRON_TZ=America/Chicago 0 16 13 * * Mon-Fri"
+                .parse()
+                .unwrap();
+            assert_eq!(Some(Chicago), schedule.timezone());
+
+            let datetimes: Vec<_> = schedule.upcoming(Chicago).take(3).collect();
+            assert!(datetimes.iter().all(|datetime| datetime.hour() == 13));
+            assert!(datetimes[0] > Utc::now());
+
+            // Friday, June 9th 2023, when Chicago was at UTC-5.
+            let start = Utc.with_ymd_and_hms(2023, 6, 9, 12, 0, 0).unwrap();
+            let next = Utc.with_ymd_and_hms(2023, 6, 9, 18, 16, 0).unwrap();
+            assert_eq!(Some(next), schedule.after(&start));
+            assert_eq!(
+                Some(next.with_timezone(&New_York)),
+                schedule.after(&start.with_timezone(&New_York))
+            );
+            assert!(schedule.matches(&next));
+            assert_eq!(
+                Some(Utc.with_ymd_and_hms(2023, 6, 8, 18, 16, 0).unwrap()),
+                schedule.before(&next)
+            );
+        }
+
+        #[cfg(feature = "chrono-tz")]
+        #[test]
+        fn cron_tz_runs_at_the_same_instants_in_any_timezone() {
+            use chrono_tz::{America::Chicago, Asia::Tokyo};
+
+            let mut schedule: Schedule = "CRON_TZ=America/Chicago 0 16 13 * * Mon-Fri"
+                .parse()
+                .unwrap();
+            let expected: Vec<_> = schedule.upcoming(Chicago).take(5).collect();
+            assert!(expected.iter().all(|datetime| datetime.hour() == 13));
+
+            assert_eq!(expected, schedule.upcoming(Utc).take(5).collect::<Vec<_>>());
+            assert_eq!(
+                expected,
+                schedule.upcoming(New_York).take(5).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                expected,
+                schedule.upcoming_owned(Tokyo).take(5).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                expected,
+                schedule.iter_with_timezone(Utc).take(5).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                expected,
+                schedule
+                    .into_iter_with_timezone(Tokyo)
+                    .take(5)
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[cfg(feature = "chrono-tz")]
+        #[test]
+        fn cron_tz_round_trips() {
+            for (expression, expected) in [
+                (
+                    "CRON_TZ=America/Chicago 00 16 13 * * mon-fri",
+                    "CRON_TZ=America/Chicago 0 16 13 * * Mon-Fri",
+                ),
+                (
+                    "CRON_TZ=Europe/London @daily",
+                    "CRON_TZ=Europe/London 0 0 0 * * *",
+                ),
+                ("CRON_TZ=UTC */5 * * * *", "CRON_TZ=UTC 0 */5 * * * *"),
+            ] {
+                let schedule: Schedule = expression.parse().unwrap();
+                assert_eq!(expected, schedule.to_string(), "{expression:?}");
+                let reparsed: Schedule = expected.parse().unwrap();
+                assert_eq!(schedule.timezone(), reparsed.timezone());
+            }
+
+            let schedule: Schedule = "CRON_TZ=America/Chicago 0 0 9 * * *".parse().unwrap();
+            assert_eq!("At 09:00:00, America/Chicago time", schedule.describe());
+        }
+
+        #[test]
+        fn schedules_that_never_run_are_turned_down() {
+            for expression in [
+                "0 0 0 30 2 *",
+                "0 0 0 31 4,6,9,11 *",
+                "0 0 0 30,31 Feb *",
+                "0 0 0 29 2 * 2025-2027",
+                "0 0 0 ? Feb 5#5 2023-2024",
+            ] {
+                assert_eq!(
+                    Err(Error::NeverRuns),
+                    expression.parse::<Schedule>().map(|_| ()),
+                    "{expression:?}"
+                );
+            }
+
+            for expression in [
+                "0 0 0 29 2 *",
+                "0 0 0 31 4,6,9,11,12 *",
+                "0 0 0 30 2 Mon",
+                "0 0 0 ? 2 5#5",
+                "0 0 0 * * * 1970",
+                "@every 1h",
+            ] {
+                let schedule: Schedule = expression.parse().unwrap();
+                assert!(schedule.is_satisfiable(), "{expression:?}");
+            }
+        }
+
+        #[test]
+        fn equivalent_ignores_how_fields_are_written() {
+            for (first, second) in [
+                ("0 */15 * * * *", "0 0,15,30,45 * * * *"),
+                ("0 16 13-18 * * Mon-Fri", "0 16 13,14,15,16,17,18 ? * 1-5"),
+                ("0 0 12 * * 0", "0 0 12 ? * 7"),
+                ("0 0 0 1 1 *", "@yearly"),
+                ("0 0 0 L 4,6,9,11 *", "0 0 0 30 Apr,Jun,Sep,Nov *"),
+                ("0 0 0 ? Feb 1L 2023", "0 0 0 27 2 ? 2023"),
+                ("0 0 0 * * * 2023-2025", "0 0 0 ? * Sun-Sat 2023,2024,2025"),
+                ("@every 90m", "@every 1h30m"),
+            ] {
+                let first: Schedule = first.parse().unwrap();
+                let second: Schedule = second.parse().unwrap();
+                assert!(first.equivalent(&second), "{first} and {second}");
+                assert!(second.equivalent(&first), "{second} and {first}");
+            }
+
+            for (first, second) in [
+                ("0 */15 * * * *", "0 0,15,30 * * * *"),
+                ("0 0 0 L * *", "0 0 0 31 * *"),
+                ("0 0 12 1 * Mon", "0 0 12 1 * *"),
+                ("0 0 0 * * *", "0 0 0 * * * 1970-2099"),
+                ("@every 1h", "0 0 * * * *"),
+            ] {
+                let first: Schedule = first.parse().unwrap();
+                let second: Schedule = second.parse().unwrap();
+                assert!(!first.equivalent(&second), "{first} and {second}");
+            }
+        }
+
+        #[test]
+        fn is_subset_of_compares_every_field() {
+            for (smaller, larger) in [
+                ("0 0 9 * * Mon", "0 0 9 * * *"),
+                ("0 0,30 * * * *", "0 */15 * * * *"),
+                ("0 0 0 15W * ?", "0 0 0 ? * Mon-Fri"),
+                ("0 0 0 ? * 5L", "0 0 0 22-31 * *"),
+                ("0 0 0 29 2 * 2024", "0 0 0 * * *"),
+                ("0 0 0 1 * *", "0 0 0 1,15 * Fri"),
+                ("@every 2h", "@every 30m"),
+            ] {
+                let smaller: Schedule = smaller.parse().unwrap();
+                let larger: Schedule = larger.parse().unwrap();
+                assert!(smaller.is_subset_of(&larger), "{smaller} in {larger}");
+                assert!(!larger.is_subset_of(&smaller), "{larger} in {smaller}");
+            }
+        }
+
+        #[test]
+        fn cron_tz_needs_a_known_timezone() {
+            let error = "CRON_TZ=Mars/Olympus_Mons 0 0 9 * * *"
+                .parse::<Schedule>()
+                .unwrap_err();
+            assert_eq!(Some(8..25), error.span());
+            assert_eq!(
+                "unknown timezone `Mars/Olympus_Mons` at column 9",
+                error.to_string()
+            );
+
+            #[cfg(feature = "chrono-tz")]
+            assert_eq!(
+                Err(Error::Empty),
+                "CRON_TZ=UTC".parse::<Schedule>().map(|_| ())
+            );
+        }
+
+        #[test]
+        fn hashed_fields_come_from_the_key() {
+            let expression = "H H(9-17) * * Mon-Fri";
+            let backup = Schedule::parse_with_key(expression, "backup").unwrap();
+            // Pinned, so that a change to the hash can't quietly
+            // move every job that's already been scheduled.
+            assert_eq!("0 11 10 * * Mon-Fri", backup.to_string());
+            assert_eq!(
+                backup.to_string(),
+                Schedule::parse_with_key(expression, "backup")
+                    .unwrap()
+                    .to_string()
+            );
+
+            let error = expression.parse::<Schedule>().unwrap_err();
+            assert_eq!(Some(0..1), error.span());
+            assert_eq!("`H` at column 1 needs a key to hash", error.to_string());
+        }
+
+        #[test]
+        fn matches_checks_every_field() {
+            let schedule: Schedule = "0 16 13-18 * * Mon-Fri".parse().unwrap();
+
+            // Friday, June 9th 2023
+            assert!(schedule.matches(&Utc.with_ymd_and_hms(2023, 6, 9, 13, 16, 0).unwrap()));
+            assert!(schedule.matches(
+                &(Utc.with_ymd_and_hms(2023, 6, 9, 18, 16, 0).unwrap()
+                    + Duration::milliseconds(500))
+            ));
+            assert!(!schedule.matches(&Utc.with_ymd_and_hms(2023, 6, 9, 19, 16, 0).unwrap()));
+            assert!(!schedule.matches(&Utc.with_ymd_and_hms(2023, 6, 9, 13, 16, 1).unwrap()));
+            assert!(!schedule.matches(&Utc.with_ymd_and_hms(2023, 6, 10, 13, 16, 0).unwrap()));
+
+            let local = New_York
+                .with_ymd_and_hms(2023, 6, 9, 13, 16, 0)
+                .unwrap()
+                .naive_local();
+            assert!(schedule.matches_local(&local));
+        }
+
+        #[test]
+        fn matches_agrees_with_iter_from() {
+            let schedule: Schedule = "0 0/20 9-10 L,15W * * 2023-2024".parse().unwrap();
+            let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+            let datetimes: Vec<_> = schedule.iter_from(&start).collect();
+            assert!(datetimes.iter().all(|datetime| schedule.matches(datetime)));
+            for pair in datetimes.windows(2) {
+                assert!(!schedule.matches(&(pair[0] + Duration::minutes(10))));
+            }
+        }
+
+        #[test]
+        fn matches_follows_the_dst_policy() {
+            use super::{AmbiguousTime, DstPolicy, NonexistentTime};
+            use chrono_tz::America::Chicago;
+
+            let schedule = "0 30 1,2 * * *"
+                .parse::<Schedule>()
+                .unwrap()
+                .with_dst_policy(DstPolicy::new(
+                    NonexistentTime::ShiftForward,
+                    AmbiguousTime::Latest,
+                ));
+            // The clocks jumped forward over 2:30 AM on March 12th, 2023,
+            // and went through 1:30 AM twice on November 5th, 2023.
+            let shifted = Chicago.with_ymd_and_hms(2023, 3, 12, 3, 30, 0).unwrap();
+            let first = Utc.with_ymd_and_hms(2023, 11, 5, 6, 30, 0).unwrap();
+            let second = Utc.with_ymd_and_hms(2023, 11, 5, 7, 30, 0).unwrap();
+
+            assert!(schedule.matches(&shifted));
+            assert!(!schedule.matches(&first.with_timezone(&Chicago)));
+            assert!(schedule.matches(&second.with_timezone(&Chicago)));
+        }
+
+        #[test]
+        fn every_never_matches() {
+            let schedule: Schedule = "@every 1s".parse().unwrap();
+            assert!(!schedule.matches(&Utc::now()));
+        }
+
+        #[test]
+        fn macros_match_the_whole_name() {
+            for expression in ["@hamburger", "@weekday", "@d", "@", "@DAILY"] {
+                assert!(
+                    matches!(
+                        expression.parse::<Schedule>(),
+                        Err(Error::InvalidMacro { .. })
+                    ),
+                    "{expression:?} should not parse"
+                );
+            }
+            assert!(matches!(
+                "@daily 0".parse::<Schedule>(),
+                Err(Error::WrongNumberOfFields { .. })
+            ));
+        }
+
+        #[test]
+        fn macros_follow_their_schedules() {
+            let mut schedule: Schedule = "@daily".parse().unwrap();
+            for datetime in schedule.iter_with_timezone(Utc).take(5) {
+                assert_eq!(
+                    (0, 0, 0),
+                    (datetime.hour(), datetime.minute(), datetime.second())
+                );
+            }
+
+            let mut schedule: Schedule = "@weekly".parse().unwrap();
+            for datetime in schedule.iter_with_timezone(Utc).take(5) {
+                assert_eq!(Weekday::Sun, datetime.weekday());
+                assert_eq!(0, datetime.hour());
+            }
+
+            let mut schedule: Schedule = "@annually".parse().unwrap();
+            for datetime in schedule.iter_with_timezone(Utc).take(3) {
+                assert_eq!(
+                    (1, 1, 0),
+                    (datetime.month(), datetime.day(), datetime.hour())
+                );
+            }
+        }
+
+        #[test]
+        fn every_is_a_fixed_interval() {
+            let mut schedule: Schedule = "@every 1h30m".parse().unwrap();
+            let datetimes: Vec<_> = schedule.iter_with_timezone(Utc).take(5).collect();
+
+            assert!(datetimes[0] > Utc::now());
+            for pair in datetimes.windows(2) {
+                assert_eq!(Duration::minutes(90), pair[1] - pair[0]);
+            }
+
+            let at = Utc.with_ymd_and_hms(2023, 6, 9, 9, 0, 0).unwrap();
+            assert_eq!(
+                Some(Utc.with_ymd_and_hms(2023, 6, 9, 7, 30, 0).unwrap()),
+                schedule.before(&at)
+            );
+        }
+
+        #[test]
+        fn macro_errors_point_at_the_token() {
+            let error = "  @hamburger".parse::<Schedule>().unwrap_err();
+            assert_eq!("unknown macro `@hamburger` at column 3", error.to_string());
+
+            let error = "@every 1h1h".parse::<Schedule>().unwrap_err();
+            assert_eq!(Some(7..11), error.span());
+            assert_eq!("invalid interval `1h1h` at column 8", error.to_string());
+
+            let error = "@every".parse::<Schedule>().unwrap_err();
+            assert_eq!("missing interval at column 7", error.to_string());
+        }
+
+        #[test]
+        fn errors_work_with_question_mark() {
+            fn parse() -> Result<Schedule, Box<dyn std::error::Error>> {
+                Ok("0 0 0 32 * *".parse()?)
+            }
+            let error = parse().unwrap_err();
+            assert_eq!(
+                "day of the month 32 out of range 1-31 at column 7",
+                error.to_string()
+            );
+        }
+
+        #[test]
+        fn every_needs_a_valid_interval() {
+            for expression in ["@every", "@every 90", "@every 0m", "@every 30m1h"] {
+                assert!(
+                    matches!(
+                        expression.parse::<Schedule>(),
+                        Err(Error::InvalidInterval { .. })
+                    ),
+                    "{expression:?} should not parse"
+                );
+            }
+            assert!(matches!(
+                "@every 1h 30m".parse::<Schedule>(),
+                Err(Error::WrongNumberOfFields { .. })
+            ));
+        }
+
+        #[test]
+        fn repeating_intervals_count_from_their_start() {
+            let interval = "R10/2023-06-01T13:16:00-05:00/PT1H";
+            let schedule = Schedule::from_repeating_interval(interval).unwrap();
+            let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+            let times: Vec<_> = schedule.iter_from(&start).collect();
+            assert_eq!(10, times.len());
+            assert_eq!(
+                Utc.with_ymd_and_hms(2023, 6, 1, 18, 16, 0).unwrap(),
+                times[0]
+            );
+            assert_eq!(
+                Utc.with_ymd_and_hms(2023, 6, 2, 3, 16, 0).unwrap(),
+                times[9]
+            );
+            assert!(schedule.matches(&times[3]));
+            assert_eq!(10, schedule.count_between(&start, &times[9], Utc));
+            assert_eq!(None, schedule.to_rrule());
+
+            // It writes back out as itself, and parses as an expression.
+            assert_eq!(interval, schedule.to_string());
+            let parsed: Schedule = interval.parse().unwrap();
+            assert!(parsed.equivalent(&schedule));
+
+            let daily = Schedule::from_repeating_interval("R/2023-06-01T00:00:00Z/P1D").unwrap();
+            let later = Utc.with_ymd_and_hms(2031, 1, 1, 12, 0, 0).unwrap();
+            assert_eq!(
+                Some(Utc.with_ymd_and_hms(2031, 1, 2, 0, 0, 0).unwrap()),
+                daily.after(&later)
+            );
+            assert!(!daily.equivalent(&"@every 1d".parse().unwrap()));
+
+            let error = "R10/2023-06-01/PT1H".parse::<Schedule>().unwrap_err();
+            assert_eq!(
+                "invalid repeating interval `2023-06-01` at column 5",
+                error.to_string()
+            );
+        }
+    }
 }
 
 fn days_in_a_month(month: u8, year: u32) -> u8 {
@@ -173,6 +1871,8 @@ fn days_in_a_month(month: u8, year: u32) -> u8 {
     }
 }
 
+// `u32::is_multiple_of` would need Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
 fn is_leap_year(year: u32) -> bool {
     if year % 4 == 0 {
         if year % 100 == 0 {
@@ -0,0 +1,225 @@
+use super::{fields::FieldTable, Schedule};
+use crate::{Error, Field};
+use chrono::Weekday;
+use std::ops::RangeInclusive;
+
+/// Builds a `Schedule` out of typed values, for programs that
+/// would otherwise have to write out an expression just to
+/// parse it right back in.
+///
+/// Every field starts out as `*`, so only the fields that are
+/// restricted need to be given. The days work the same way as
+/// in an expression: with only `days_of_month` or `weekdays`,
+/// just that one is used, and with both, a day matches when
+/// either of them does.
+///
+/// ```
+/// use chrono::Weekday;
+/// use cron::Schedule;
+///
+/// let schedule = Schedule::builder()
+///     .seconds(0)
+///     .minutes([16])
+///     .hours(13..=18)
+///     .weekdays(Weekday::Mon..=Weekday::Fri)
+///     .build()
+///     .unwrap();
+/// assert_eq!("0 16 13-18 * * Mon-Fri", schedule.to_string());
+/// ```
+///
+/// # Failure
+///
+/// Nothing gets checked until `build`, which fails on the
+/// first field that is wrong, going from the seconds up to
+/// the years. Values have to go from lowest to highest with
+/// no repeats, or the build fails with an `UnsortedValues`,
+/// and that includes ranges that run backwards. A value past
+/// either end of its field fails with a `ValueOutOfRange`,
+/// and a field with nothing in it fails with an `EmptyField`.
+#[derive(Clone, Debug, Default)]
+pub struct ScheduleBuilder {
+    seconds: Option<Vec<u32>>,
+    minutes: Option<Vec<u32>>,
+    hours: Option<Vec<u32>>,
+    days_of_month: Option<Vec<u32>>,
+    months: Option<Vec<u32>>,
+    weekdays: Option<Vec<u32>>,
+    years: Option<Vec<u32>>,
+}
+
+impl ScheduleBuilder {
+    /// The seconds, from 0 through 59.
+    pub fn seconds(mut self, seconds: impl Values<u8>) -> Self {
+        self.seconds = Some(seconds.numbers());
+        self
+    }
+
+    /// The minutes, from 0 through 59.
+    pub fn minutes(mut self, minutes: impl Values<u8>) -> Self {
+        self.minutes = Some(minutes.numbers());
+        self
+    }
+
+    /// The hours, from 0 through 23.
+    pub fn hours(mut self, hours: impl Values<u8>) -> Self {
+        self.hours = Some(hours.numbers());
+        self
+    }
+
+    /// The days of the month, from 1 through 31.
+    pub fn days_of_month(mut self, days: impl Values<u8>) -> Self {
+        self.days_of_month = Some(days.numbers());
+        self
+    }
+
+    /// The months, from 1 for January through 12 for December.
+    pub fn months(mut self, months: impl Values<u8>) -> Self {
+        self.months = Some(months.numbers());
+        self
+    }
+
+    /// The days of the week. Just like in an expression, the
+    /// week starts on Sunday, so the weekend is `[Sun, Sat]`.
+    pub fn weekdays(mut self, weekdays: impl Values<Weekday>) -> Self {
+        self.weekdays = Some(weekdays.numbers());
+        self
+    }
+
+    /// The years that the schedule runs in, from 1970 through
+    /// 2099. The schedule stops once the last of them is over.
+    pub fn years(mut self, years: impl Values<u32>) -> Self {
+        self.years = Some(years.numbers());
+        self
+    }
+
+    /// Checks every field, and builds the schedule out of them.
+    pub fn build(self) -> Result<Schedule, Error> {
+        let field = |field: Field, values: Option<Vec<u32>>| match values {
+            Some(values) => check(field, values).map(narrow),
+            None => Ok(narrow((field.min()..=field.max()).collect())),
+        };
+        let days = |field: Field, values: Option<Vec<u32>>| {
+            values
+                .map(|values| check(field, values).map(narrow))
+                .transpose()
+        };
+        let seconds = field(Field::Seconds, self.seconds)?;
+        let minutes = field(Field::Minutes, self.minutes)?;
+        let hours = field(Field::Hours, self.hours)?;
+        let days_of_month = days(Field::DaysOfMonth, self.days_of_month)?;
+        let months = field(Field::Months, self.months)?;
+        let weekdays = days(Field::DaysOfWeek, self.weekdays)?;
+        let years = self
+            .years
+            .map(|years| check(Field::Years, years))
+            .transpose()?;
+
+        let mut builder = FieldTable::builder();
+        builder
+            .with_secs_iter(seconds)
+            .with_mins_iter(minutes)
+            .with_hrs_iter(hours)
+            .with_months_iter(months);
+        match (days_of_month, weekdays) {
+            (Some(month), Some(week)) => builder.with_days_of_both_iter(week, month),
+            (None, Some(week)) => builder.with_days_of_the_week_only_iter(week),
+            (Some(month), None) => builder.with_days_of_the_month_only_iter(month),
+            (None, None) => builder.with_days_of_the_month_only_iter(1..=31),
+        };
+        if let Some(years) = years {
+            builder.with_years_iter(years);
+        }
+        Ok(Schedule::from(builder.build()?))
+    }
+}
+
+/// Makes sure that `values` go from lowest to highest,
+/// and that they all fit in `field`.
+fn check(field: Field, values: Vec<u32>) -> Result<Vec<u32>, Error> {
+    if values.is_empty() {
+        return Err(Error::EmptyField(field));
+    }
+    if let Some(pair) = values.windows(2).find(|pair| pair[0] >= pair[1]) {
+        return Err(Error::UnsortedValues {
+            field,
+            value: pair[1],
+            previous: pair[0],
+        });
+    }
+    let (min, max) = (field.min(), field.max());
+    match values.iter().find(|value| !(min..=max).contains(*value)) {
+        Some(&value) => Err(Error::ValueOutOfRange {
+            field,
+            value,
+            min,
+            max,
+        }),
+        None => Ok(values),
+    }
+}
+
+/// Every field besides the years fits inside of a `u8`,
+/// once `check` has made sure that it's in range.
+fn narrow(values: Vec<u32>) -> Vec<u8> {
+    values.into_iter().map(|value| value as u8).collect()
+}
+
+/// Anything that can be given to a field of the `ScheduleBuilder`:
+/// a single value, an array, slice or `Vec` of them, or an
+/// inclusive range like `13..=18` or `Weekday::Mon..=Weekday::Fri`.
+pub trait Values<T> {
+    /// Returns the values as the numbers they'd be written as
+    /// in an expression, in the order that they were given.
+    fn numbers(self) -> Vec<u32>;
+}
+
+macro_rules! values {
+    ($($value:ty => $number:expr),*) => {
+        $(
+            impl Values<$value> for $value {
+                fn numbers(self) -> Vec<u32> {
+                    vec![$number(self)]
+                }
+            }
+
+            impl<const N: usize> Values<$value> for [$value; N] {
+                fn numbers(self) -> Vec<u32> {
+                    self.into_iter().map($number).collect()
+                }
+            }
+
+            impl Values<$value> for &[$value] {
+                fn numbers(self) -> Vec<u32> {
+                    self.iter().copied().map($number).collect()
+                }
+            }
+
+            impl Values<$value> for Vec<$value> {
+                fn numbers(self) -> Vec<u32> {
+                    self.into_iter().map($number).collect()
+                }
+            }
+
+            /// A range that runs backwards comes out as just its
+            /// two ends, so that it gets caught as being unsorted.
+            impl Values<$value> for RangeInclusive<$value> {
+                fn numbers(self) -> Vec<u32> {
+                    let (start, end) = ($number(*self.start()), $number(*self.end()));
+                    match start <= end {
+                        true => (start..=end).collect(),
+                        false => vec![start, end],
+                    }
+                }
+            }
+        )*
+    };
+}
+
+values!(
+    u8 => u32::from,
+    u32 => std::convert::identity,
+    Weekday => |day: Weekday| day.num_days_from_sunday()
+);
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,112 @@
+use crate::{schedule::Schedule, Error, Field};
+use chrono::Weekday;
+
+#[test]
+fn builds_the_same_schedule_as_an_expression() {
+    let schedule = Schedule::builder()
+        .seconds(0)
+        .minutes([16])
+        .hours(13..=18)
+        .weekdays(Weekday::Mon..=Weekday::Fri)
+        .build()
+        .unwrap();
+    assert_eq!("0 16 13-18 * * Mon-Fri", schedule.to_string());
+
+    let schedule = Schedule::builder()
+        .seconds(vec![0, 30])
+        .days_of_month(&[1, 15][..])
+        .months(6..=8)
+        .years(2024..=2026)
+        .build()
+        .unwrap();
+    assert_eq!("0,30 * * 1,15 Jun-Aug * 2024-2026", schedule.to_string());
+}
+
+#[test]
+fn fields_default_to_every_value() {
+    let schedule = Schedule::builder().build().unwrap();
+    assert_eq!("* * * * * *", schedule.to_string());
+
+    let schedule = Schedule::builder()
+        .days_of_month(1)
+        .weekdays([Weekday::Sun, Weekday::Sat])
+        .build()
+        .unwrap();
+    assert_eq!("* * * 1 * Sun,Sat", schedule.to_string());
+}
+
+#[test]
+fn values_have_to_be_sorted() {
+    assert_eq!(
+        Err(Error::UnsortedValues {
+            field: Field::Minutes,
+            value: 10,
+            previous: 30,
+        }),
+        Schedule::builder().minutes([0, 30, 10]).build().map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::UnsortedValues {
+            field: Field::Hours,
+            value: 9,
+            previous: 9,
+        }),
+        Schedule::builder().hours([9, 9]).build().map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::UnsortedValues {
+            field: Field::DaysOfWeek,
+            value: 1,
+            previous: 5,
+        }),
+        Schedule::builder()
+            .weekdays(Weekday::Fri..=Weekday::Mon)
+            .build()
+            .map(|_| ())
+    );
+}
+
+#[test]
+fn values_have_to_be_in_range() {
+    let error = Schedule::builder().hours(20..=24).build().unwrap_err();
+    assert_eq!(
+        Error::ValueOutOfRange {
+            field: Field::Hours,
+            value: 24,
+            min: 0,
+            max: 23,
+        },
+        error
+    );
+    assert_eq!("hour 24 out of range 0-23", error.to_string());
+
+    for (builder, field) in [
+        (Schedule::builder().days_of_month(0), Field::DaysOfMonth),
+        (Schedule::builder().months(13), Field::Months),
+        (Schedule::builder().years(2100), Field::Years),
+    ] {
+        assert!(matches!(
+            builder.build(),
+            Err(Error::ValueOutOfRange { field: found, .. }) if found == field
+        ));
+    }
+}
+
+#[test]
+fn fields_cant_be_empty() {
+    assert_eq!(
+        Err(Error::EmptyField(Field::Seconds)),
+        Schedule::builder().seconds(vec![]).build().map(|_| ())
+    );
+}
+
+#[test]
+fn errors_come_from_the_first_wrong_field() {
+    assert!(matches!(
+        Schedule::builder().years(1900).seconds(60).build(),
+        Err(Error::ValueOutOfRange {
+            field: Field::Seconds,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,238 @@
+use chrono::{Duration, NaiveDate};
+use std::{fmt, ops::RangeInclusive};
+
+mod ical;
+
+/// A set of days that a schedule shouldn't run on,
+/// like holidays or breaks.
+///
+/// The days are kept as sorted ranges with no overlaps, so
+/// a calendar can hold months-long breaks without storing
+/// every day in them. Calendars can be built by hand, or
+/// read from a CSV file with `from_csv` or an iCalendar
+/// file with `from_ical`.
+///
+/// ```
+/// use cron::schedule::{Calendar, Schedule};
+///
+/// let calendar = Calendar::from_csv("2023-03-13,2023-03-17,Spring break").unwrap();
+/// let schedule: Schedule = "0 16 13 * * Mon-Fri".parse().unwrap();
+/// let schedule = schedule.with_calendar(&calendar);
+/// # let _ = schedule;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Calendar {
+    ranges: Vec<RangeInclusive<NaiveDate>>,
+}
+
+/// Everything that can go wrong while reading a `Calendar`.
+/// Lines are counted from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CalendarError {
+    /// Something that should have been a date but isn't.
+    InvalidDate { line: usize, token: String },
+    /// An event `DURATION` that isn't written like `P1D`,
+    /// `P2W` or `PT12H`, or that goes backwards.
+    InvalidDuration { line: usize, token: String },
+    /// A range that ends before it starts.
+    BackwardsRange { line: usize },
+    /// An event that never says which day it starts on.
+    MissingStart { line: usize },
+    /// An event that starts but never ends.
+    UnclosedEvent { line: usize },
+    /// An event property that changes which days the event
+    /// covers, but that calendars don't understand yet.
+    Unsupported { line: usize, property: String },
+}
+
+impl fmt::Display for CalendarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalendarError::InvalidDate { line, token } => {
+                write!(f, "invalid date `{token}` on line {line}")
+            }
+            CalendarError::InvalidDuration { line, token } => {
+                write!(f, "invalid duration `{token}` on line {line}")
+            }
+            CalendarError::BackwardsRange { line } => {
+                write!(f, "the range on line {line} ends before it starts")
+            }
+            CalendarError::MissingStart { line } => {
+                write!(f, "the event on line {line} has no start date")
+            }
+            CalendarError::UnclosedEvent { line } => {
+                write!(f, "the event on line {line} is never closed")
+            }
+            CalendarError::Unsupported { line, property } => {
+                write!(f, "unsupported property `{property}` on line {line}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalendarError {}
+
+impl Calendar {
+    /// Creates a calendar without any days in it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single day to the calendar.
+    pub fn with_date(self, date: NaiveDate) -> Self {
+        self.with_range(date..=date)
+    }
+
+    /// Adds every day from the start of `range` to its end,
+    /// including both. An empty range doesn't add anything.
+    pub fn with_range(mut self, range: RangeInclusive<NaiveDate>) -> Self {
+        self.insert(range);
+        self
+    }
+
+    /// Returns true if `date` is one of the days in the calendar.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.range_containing(date).is_some()
+    }
+
+    /// Returns true if the calendar doesn't have any days in it.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterates over the days in the calendar as ranges, in order.
+    /// Ranges that overlap or touch are merged into one.
+    pub fn ranges(&self) -> impl Iterator<Item = &RangeInclusive<NaiveDate>> {
+        self.ranges.iter()
+    }
+
+    /// Returns the range of days in the calendar that `date`
+    /// is part of, so that a search can skip all of them at once.
+    pub(super) fn range_containing(&self, date: NaiveDate) -> Option<&RangeInclusive<NaiveDate>> {
+        let after = self.ranges.partition_point(|range| *range.start() <= date);
+        after
+            .checked_sub(1)
+            .map(|index| &self.ranges[index])
+            .filter(|range| date <= *range.end())
+    }
+
+    fn insert(&mut self, range: RangeInclusive<NaiveDate>) {
+        if range.is_empty() {
+            return;
+        }
+        let at = self
+            .ranges
+            .partition_point(|other| other.start() < range.start());
+        self.ranges.insert(at, range);
+
+        // Merge everything that overlaps or touches into its
+        // neighbour on the left, keeping the ranges sorted.
+        let mut merged: Vec<RangeInclusive<NaiveDate>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= *last.end() + Duration::days(1) => {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+
+    /// Adds every day of `other` to this calendar.
+    pub fn merge(&mut self, other: &Calendar) {
+        for range in other.ranges() {
+            self.insert(range.clone());
+        }
+    }
+
+    /// Reads a calendar with one day or range of days per line.
+    ///
+    /// Each line is a date like `2023-07-04`, optionally followed
+    /// by a comma and the last date of a range, and then by
+    /// anything else, like a name. Blank lines, lines starting
+    /// with `#`, and a header line are skipped, so
+    ///
+    /// ```text
+    /// start,end,name
+    /// 2023-03-13,2023-03-17,Spring break
+    /// 2023-07-04,,Independence Day
+    /// ```
+    ///
+    /// is a calendar with six days in it.
+    pub fn from_csv(csv: &str) -> Result<Self, CalendarError> {
+        let mut calendar = Calendar::new();
+        let mut first = true;
+        for (index, line) in csv.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut columns = line.split(',').map(str::trim);
+            let start = columns.next().unwrap_or_default();
+            let is_header = first && !start.starts_with(|c: char| c.is_ascii_digit());
+            first = false;
+            if is_header {
+                continue;
+            }
+
+            let date = |token: &str| {
+                NaiveDate::parse_from_str(token, "%Y-%m-%d").map_err(|_| {
+                    CalendarError::InvalidDate {
+                        line: line_number,
+                        token: token.to_owned(),
+                    }
+                })
+            };
+            let start = date(start)?;
+            let end = match columns.next() {
+                Some(end) if !end.is_empty() => date(end)?,
+                _ => start,
+            };
+            if end < start {
+                return Err(CalendarError::BackwardsRange { line: line_number });
+            }
+            calendar.insert(start..=end);
+        }
+        Ok(calendar)
+    }
+
+    /// Reads the days covered by the events of an iCalendar
+    /// (`.ics`) file, the format that most calendar apps export.
+    ///
+    /// An event covers the days from its `DTSTART` up to its
+    /// `DTEND` or `DURATION`, which is how holidays usually
+    /// come. Times of day are ignored, so an event that covers
+    /// any part of a day covers the whole day. Repeating
+    /// events aren't understood, and are an error.
+    pub fn from_ical(ical: &str) -> Result<Self, CalendarError> {
+        let mut calendar = Calendar::new();
+        for range in ical::events(ical)? {
+            calendar.insert(range);
+        }
+        Ok(calendar)
+    }
+}
+
+impl FromIterator<NaiveDate> for Calendar {
+    fn from_iter<I: IntoIterator<Item = NaiveDate>>(dates: I) -> Self {
+        let mut calendar = Calendar::new();
+        calendar.extend(dates);
+        calendar
+    }
+}
+
+impl Extend<NaiveDate> for Calendar {
+    fn extend<I: IntoIterator<Item = NaiveDate>>(&mut self, dates: I) {
+        for date in dates {
+            self.insert(date..=date);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
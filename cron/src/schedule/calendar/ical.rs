@@ -0,0 +1,252 @@
+//! Just enough of iCalendar (RFC 5545) to find out
+//! which days the events in a file cover.
+
+use super::CalendarError;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::ops::RangeInclusive;
+
+/// Properties that make an event happen on more days than
+/// its start and end, which calendars can't follow yet.
+const REPEATING: [&str; 3] = ["RRULE", "RDATE", "EXRULE"];
+
+/// One property of a component, like `DTSTART;VALUE=DATE:20230704`,
+/// after its continuation lines have been joined back onto it.
+struct Property<'a> {
+    line: usize,
+    name: String,
+    value: &'a str,
+}
+
+/// When an event starts or ends, which is either a whole
+/// day, or a time of day that is read as the day it's on.
+#[derive(Clone, Copy)]
+enum Moment {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl Moment {
+    fn date(self) -> NaiveDate {
+        match self {
+            Moment::Date(date) => date,
+            Moment::DateTime(datetime) => datetime.date(),
+        }
+    }
+
+    /// Returns the last day that an event ending at this moment
+    /// covers. The end isn't part of the event, so an event that
+    /// ends at midnight or on a date stops the day before.
+    fn last_day_before(self) -> NaiveDate {
+        match self {
+            Moment::DateTime(datetime) if datetime.time() != NaiveTime::MIN => datetime.date(),
+            moment => moment.date().pred_opt().unwrap_or(NaiveDate::MIN),
+        }
+    }
+}
+
+/// The parts of a `VEVENT` that decide which days it covers.
+struct Event {
+    line: usize,
+    start: Option<Moment>,
+    end: Option<Moment>,
+    duration: Option<Duration>,
+}
+
+impl Event {
+    fn new(line: usize) -> Self {
+        Self {
+            line,
+            start: None,
+            end: None,
+            duration: None,
+        }
+    }
+
+    fn days(self) -> Result<RangeInclusive<NaiveDate>, CalendarError> {
+        let start = self
+            .start
+            .ok_or(CalendarError::MissingStart { line: self.line })?;
+        let end = match (self.end, self.duration) {
+            (Some(end), _) => Some(end),
+            (None, Some(duration)) => {
+                let end = match start {
+                    Moment::Date(date) => date
+                        .checked_add_signed(Duration::days(duration.num_days()))
+                        .map(Moment::Date),
+                    Moment::DateTime(datetime) => {
+                        datetime.checked_add_signed(duration).map(Moment::DateTime)
+                    }
+                };
+                Some(end.ok_or(CalendarError::InvalidDuration {
+                    line: self.line,
+                    token: duration.to_string(),
+                })?)
+            }
+            (None, None) => None,
+        };
+        let last = match end {
+            Some(end) if end.date() < start.date() => {
+                return Err(CalendarError::BackwardsRange { line: self.line })
+            }
+            // A day-long event can also just end where it started.
+            Some(end) => end.last_day_before().max(start.date()),
+            None => start.date(),
+        };
+        Ok(start.date()..=last)
+    }
+}
+
+/// Reads the days covered by each of the events in `ical`.
+pub(super) fn events(ical: &str) -> Result<Vec<RangeInclusive<NaiveDate>>, CalendarError> {
+    let lines = unfold(ical);
+    let mut events = Vec::new();
+    // The components that are open, innermost last, so that the
+    // properties of something like a `VALARM` inside an event
+    // don't get mixed up with the event's own.
+    let mut components: Vec<String> = Vec::new();
+    let mut event: Option<Event> = None;
+
+    for (line, content) in &lines {
+        let Some(property) = property(*line, content) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" => {
+                let component = property.value.trim().to_ascii_uppercase();
+                if component == "VEVENT" && components.last().is_none_or(|c| c == "VCALENDAR") {
+                    event = Some(Event::new(property.line));
+                }
+                components.push(component);
+            }
+            "END" => {
+                if components.pop().as_deref() == Some("VEVENT") {
+                    if let Some(event) = event.take() {
+                        events.push(event.days()?);
+                    }
+                }
+            }
+            name => {
+                let Some(event) = event.as_mut() else {
+                    continue;
+                };
+                if components.last().map(String::as_str) != Some("VEVENT") {
+                    continue;
+                }
+                match name {
+                    "DTSTART" => event.start = Some(moment(&property)?),
+                    "DTEND" => event.end = Some(moment(&property)?),
+                    "DURATION" => event.duration = Some(duration(&property)?),
+                    name if REPEATING.contains(&name) => {
+                        return Err(CalendarError::Unsupported {
+                            line: property.line,
+                            property: name.to_owned(),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    match event {
+        Some(event) => Err(CalendarError::UnclosedEvent { line: event.line }),
+        None => Ok(events),
+    }
+}
+
+/// Joins lines that were folded onto the next one, which
+/// start with a space or a tab, back onto the line before.
+/// Each line keeps the number that it started on.
+fn unfold(ical: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in ical.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((index + 1, line.to_owned())),
+        }
+    }
+    lines
+}
+
+/// Splits a content line into its name and value. Parameters
+/// are skipped, keeping in mind that quoted ones can have
+/// colons in them. Returns `None` for lines without a value.
+fn property(line: usize, content: &str) -> Option<Property<'_>> {
+    let mut quoted = false;
+    let colon = content.char_indices().find_map(|(at, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(at),
+        _ => None,
+    })?;
+    let name_end = content[..colon].find(';').unwrap_or(colon);
+    Some(Property {
+        line,
+        name: content[..name_end].trim().to_ascii_uppercase(),
+        value: content[colon + 1..].trim(),
+    })
+}
+
+/// Reads a `DATE` like `20230704`, or a `DATE-TIME`
+/// like `20230704T090000` with or without a `Z`.
+fn moment(property: &Property) -> Result<Moment, CalendarError> {
+    let invalid = || CalendarError::InvalidDate {
+        line: property.line,
+        token: property.value.to_owned(),
+    };
+    let value = property.value.trim_end_matches(['Z', 'z']);
+    match value.split_once(['T', 't']) {
+        Some(_) => NaiveDateTime::parse_from_str(&value.to_ascii_uppercase(), "%Y%m%dT%H%M%S")
+            .map(Moment::DateTime)
+            .map_err(|_| invalid()),
+        None => NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(Moment::Date)
+            .map_err(|_| invalid()),
+    }
+}
+
+/// Reads a `DURATION` like `P1D`, `P2W` or `P1DT12H`.
+/// Durations that go backwards don't make sense for an
+/// event, so they aren't allowed.
+fn duration(property: &Property) -> Result<Duration, CalendarError> {
+    let invalid = || CalendarError::InvalidDuration {
+        line: property.line,
+        token: property.value.to_owned(),
+    };
+    let value = property.value.to_ascii_uppercase();
+    let value = value.strip_prefix('+').unwrap_or(&value);
+    let rest = value.strip_prefix('P').ok_or_else(invalid)?;
+
+    let mut seconds: i64 = 0;
+    let mut in_time = false;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if !in_time && number.is_empty() => in_time = true,
+            unit => {
+                let amount = number.parse::<i64>().map_err(|_| invalid())?;
+                number.clear();
+                let unit = match (unit, in_time) {
+                    ('W', false) => 7 * 86_400,
+                    ('D', false) => 86_400,
+                    ('H', true) => 3_600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return Err(invalid()),
+                };
+                seconds = amount
+                    .checked_mul(unit)
+                    .and_then(|amount| seconds.checked_add(amount))
+                    .ok_or_else(invalid)?;
+            }
+        }
+    }
+    if !number.is_empty() || rest.is_empty() {
+        return Err(invalid());
+    }
+    // `Duration::seconds` panics on huge values, and this doesn't.
+    Duration::from_std(std::time::Duration::from_secs(seconds as u64)).map_err(|_| invalid())
+}
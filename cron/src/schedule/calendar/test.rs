@@ -0,0 +1,193 @@
+use super::{Calendar, CalendarError};
+use crate::schedule::Schedule;
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn ranges_are_merged() {
+    let calendar = Calendar::new()
+        .with_range(date(2023, 3, 13)..=date(2023, 3, 17))
+        .with_date(date(2023, 3, 18))
+        .with_range(date(2023, 3, 15)..=date(2023, 3, 16))
+        .with_date(date(2023, 1, 2))
+        .with_range(date(2023, 5, 2)..=date(2023, 5, 1));
+
+    let ranges: Vec<_> = calendar.ranges().cloned().collect();
+    assert_eq!(
+        vec![
+            date(2023, 1, 2)..=date(2023, 1, 2),
+            date(2023, 3, 13)..=date(2023, 3, 18),
+        ],
+        ranges
+    );
+    assert!(calendar.contains(date(2023, 3, 18)));
+    assert!(!calendar.contains(date(2023, 3, 19)));
+    assert!(!calendar.contains(date(2023, 1, 1)));
+}
+
+#[test]
+fn csv_takes_days_and_ranges() {
+    let csv = "\
+start,end,name
+# Spring semester
+2023-03-13,2023-03-17,Spring break
+
+2023-07-04,,Independence Day
+2023-12-25
+";
+    let calendar = Calendar::from_csv(csv).unwrap();
+    let expected = Calendar::new()
+        .with_range(date(2023, 3, 13)..=date(2023, 3, 17))
+        .with_date(date(2023, 7, 4))
+        .with_date(date(2023, 12, 25));
+    assert_eq!(expected, calendar);
+}
+
+#[test]
+fn csv_errors_name_the_line() {
+    assert_eq!(
+        Err(CalendarError::InvalidDate {
+            line: 2,
+            token: "2023-02-30".to_owned()
+        }),
+        Calendar::from_csv("2023-01-01\n2023-02-30")
+    );
+    assert_eq!(
+        Err(CalendarError::BackwardsRange { line: 1 }),
+        Calendar::from_csv("2023-03-17,2023-03-13")
+    );
+    // Only the first line can be a header.
+    assert!(Calendar::from_csv("2023-01-01\nholiday").is_err());
+}
+
+#[test]
+fn ical_reads_all_day_events() {
+    let ical = "\
+BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VEVENT
+SUMMARY:Spring Break
+DTSTART;VALUE=DATE:20230313
+DTEND;VALUE=DATE:20230318
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Independence Day
+DTSTART;VALUE=DATE:20230704
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Thanksgiving Break
+DTSTART;VALUE=DATE:20231122
+DURATION:P3D
+BEGIN:VALARM
+TRIGGER:-PT15M
+DURATION:PT5M
+END:VALARM
+END:VEVENT
+END:VCALENDAR
+";
+    let calendar = Calendar::from_ical(ical).unwrap();
+    let expected = Calendar::new()
+        .with_range(date(2023, 3, 13)..=date(2023, 3, 17))
+        .with_date(date(2023, 7, 4))
+        .with_range(date(2023, 11, 22)..=date(2023, 11, 24));
+    assert_eq!(expected, calendar);
+}
+
+#[test]
+fn ical_reads_timed_and_folded_events() {
+    let ical = "\
+BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+SUMMARY:Finals week, with a description that goes on\r
+  for long enough to get folded\r
+DTSTART;TZID=\"America/Chicago\":20231211T080000\r
+DTEND;TZID=\"America/Chicago\":20231215T170000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART:20231220T220000Z\r
+DTEND:\r
+ 20231221T000000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    let calendar = Calendar::from_ical(ical).unwrap();
+    let expected = Calendar::new()
+        .with_range(date(2023, 12, 11)..=date(2023, 12, 15))
+        .with_date(date(2023, 12, 20));
+    assert_eq!(expected, calendar);
+}
+
+#[test]
+fn ical_errors() {
+    let event =
+        |body: &str| format!("BEGIN:VCALENDAR\nBEGIN:VEVENT\n{body}\nEND:VEVENT\nEND:VCALENDAR");
+
+    assert_eq!(
+        Err(CalendarError::Unsupported {
+            line: 4,
+            property: "RRULE".to_owned()
+        }),
+        Calendar::from_ical(&event("DTSTART;VALUE=DATE:20231225\nRRULE:FREQ=YEARLY"))
+    );
+    assert_eq!(
+        Err(CalendarError::MissingStart { line: 2 }),
+        Calendar::from_ical(&event("SUMMARY:Nothing"))
+    );
+    assert!(matches!(
+        Calendar::from_ical(&event("DTSTART:2023-12-25")),
+        Err(CalendarError::InvalidDate { line: 3, .. })
+    ));
+    assert!(matches!(
+        Calendar::from_ical(&event("DTSTART:20231225\nDURATION:-P1D")),
+        Err(CalendarError::InvalidDuration { line: 4, .. })
+    ));
+    assert_eq!(
+        Err(CalendarError::UnclosedEvent { line: 1 }),
+        Calendar::from_ical("BEGIN:VEVENT\nDTSTART:20231225")
+    );
+}
+
+#[test]
+fn schedules_skip_calendar_days() {
+    let calendar = Calendar::new()
+        .with_range(date(2023, 3, 13)..=date(2023, 3, 17))
+        .with_date(date(2023, 3, 20));
+    let schedule: Schedule = "0 16 13 * * Mon-Fri".parse().unwrap();
+    let schedule = schedule.with_calendar(&calendar);
+    let start = Utc.with_ymd_and_hms(2023, 3, 9, 0, 0, 0).unwrap();
+
+    let days: Vec<_> = schedule
+        .iter_from(&start)
+        .take(4)
+        .map(|datetime| datetime.day())
+        .collect();
+    assert_eq!(vec![9, 10, 21, 22], days);
+
+    let end = Utc.with_ymd_and_hms(2023, 3, 21, 0, 0, 0).unwrap();
+    let days: Vec<_> = schedule
+        .iter_backwards_from(&end)
+        .take(2)
+        .map(|datetime| datetime.day())
+        .collect();
+    assert_eq!(vec![10, 9], days);
+}
+
+#[test]
+fn long_breaks_are_skipped_quickly() {
+    let summer = Calendar::new().with_range(date(2023, 5, 13)..=date(2023, 8, 20));
+    let schedule: Schedule = "* * * * * *".parse().unwrap();
+    let schedule = schedule.with_calendar(&summer);
+    let start = Utc.with_ymd_and_hms(2023, 5, 12, 23, 59, 59).unwrap();
+
+    assert_eq!(
+        Some(Utc.with_ymd_and_hms(2023, 8, 21, 0, 0, 0).unwrap()),
+        schedule.after(&start)
+    );
+    assert_eq!(
+        Some(Utc.with_ymd_and_hms(2023, 5, 12, 23, 59, 59).unwrap()),
+        schedule.before(&Utc.with_ymd_and_hms(2023, 8, 21, 0, 0, 0).unwrap())
+    );
+}
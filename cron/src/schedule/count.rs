@@ -0,0 +1,175 @@
+//! Counts the datetimes that a schedule runs at, and finds the
+//! `n`th one, without stepping through them one at a time.
+//!
+//! Time gets split up into stretches where the UTC offset stays
+//! the same. Local times and instants line up one to one inside
+//! of those, so the `FieldTable` can count its local times
+//! directly. Only the moments right around an offset change,
+//! where the `DstPolicy` gets a say, are gone through one by one.
+
+use super::{dst, fields::FieldTable, Schedule};
+use chrono::{DateTime, Duration, TimeZone, Timelike};
+use std::collections::BTreeSet;
+
+/// How far a single stretch goes at most. Timezones without
+/// any offset changes would otherwise be one endless stretch.
+const STRETCH_DAYS: i64 = 366;
+
+/// What happens to the UTC offset over a stretch of time.
+enum Stretch {
+    /// It stays the same the whole way through.
+    Steady,
+    /// It changes somewhere in the middle, so local
+    /// times can be skipped, repeated or shifted.
+    Shifting,
+}
+
+/// Returns how many times `schedule` runs at or after
+/// `from`, and before `until`. Both are whole seconds.
+pub(super) fn count<Tz: TimeZone + 'static>(
+    schedule: &Schedule,
+    fields: &FieldTable,
+    from: &DateTime<Tz>,
+    until: &DateTime<Tz>,
+) -> u64 {
+    let mut from = from.clone();
+    let mut count = 0;
+    while from < *until {
+        let (stretch, end) = stretch(&from, Some(until));
+        count += match stretch {
+            Stretch::Steady => fields.count(&from.naive_local(), &end.naive_local()),
+            Stretch::Shifting => shifting(schedule, fields, &from, &end).len() as u64,
+        };
+        from = end;
+    }
+    count
+}
+
+/// Returns the `n`th datetime, counting from 1, that `schedule`
+/// runs at, out of the ones at or after `from`, a whole second.
+pub(super) fn nth<Tz: TimeZone + 'static>(
+    schedule: &Schedule,
+    fields: &FieldTable,
+    from: &DateTime<Tz>,
+    n: u64,
+) -> Option<DateTime<Tz>> {
+    let mut from = from.clone();
+    let mut left = n;
+    loop {
+        // Jumping to the next datetime keeps a schedule that rarely
+        // runs from going through one empty stretch after another,
+        // and ends the search once the schedule has stopped.
+        from = schedule.after(&(from - Duration::seconds(1)))?;
+        let (stretch, end) = stretch(&from, None);
+        match stretch {
+            Stretch::Steady => {
+                let (first, last) = (from.naive_local(), end.naive_local());
+                match fields.count(&first, &last) {
+                    count if count < left => left -= count,
+                    _ => {
+                        let local = fields.nth_from(&first, left)?;
+                        return from.timezone().from_local_datetime(&local).earliest();
+                    }
+                }
+            }
+            Stretch::Shifting => {
+                for datetime in shifting(schedule, fields, &from, &end) {
+                    match left {
+                        1 => return Some(datetime),
+                        _ => left -= 1,
+                    }
+                }
+            }
+        }
+        from = end;
+    }
+}
+
+/// Returns the stretch that starts at `from`, and where it ends,
+/// which is never later than `until`.
+///
+/// An offset change has a window around it, as long as the
+/// change on either side, that every skipped, repeated or shifted
+/// local time runs inside of. That window is a `Shifting` stretch,
+/// and everything between windows is `Steady`.
+fn stretch<Tz: TimeZone>(
+    from: &DateTime<Tz>,
+    until: Option<&DateTime<Tz>>,
+) -> (Stretch, DateTime<Tz>) {
+    let tz = from.timezone();
+    let day = Duration::days(1);
+    let limit = from.clone() + Duration::days(STRETCH_DAYS);
+    let limit = until.map_or(limit.clone(), |until| until.clone().min(limit));
+
+    // Starting a day early catches a window that `from` is already in.
+    let mut probe = from.naive_utc() - day;
+    let mut offset = dst::offset_at(&tz, &probe);
+    while probe < limit.naive_utc() {
+        let next = (probe + day).min(limit.naive_utc());
+        let next_offset = dst::offset_at(&tz, &next);
+        if next_offset != offset {
+            // Offsets change on a whole second, and `transition`
+            // finds the first instant after it to within a second.
+            let change = dst::transition(&tz, probe, next);
+            let change = change.with_nanosecond(0).unwrap_or(change);
+            let shift = (next_offset - offset).max(offset - next_offset);
+            let (start, end) = (change - shift, change + shift);
+            if end > from.naive_utc() {
+                return match start > from.naive_utc() {
+                    true => (Stretch::Steady, tz.from_utc_datetime(&start)),
+                    false => {
+                        let end = tz.from_utc_datetime(&end);
+                        (
+                            Stretch::Shifting,
+                            until.map_or(end.clone(), |until| end.min(until.clone())),
+                        )
+                    }
+                };
+            }
+            offset = next_offset;
+        }
+        probe = next;
+    }
+    (Stretch::Steady, limit)
+}
+
+/// Returns the datetimes that `schedule` runs at, at or after
+/// `from` and before `until`, going through them one by one.
+///
+/// Stepping with `Schedule::after` would work too, but each step
+/// near an offset change looks through the whole change, which
+/// adds up for schedules that run every second.
+fn shifting<Tz: TimeZone>(
+    schedule: &Schedule,
+    fields: &FieldTable,
+    from: &DateTime<Tz>,
+    until: &DateTime<Tz>,
+) -> BTreeSet<DateTime<Tz>> {
+    let tz = from.timezone();
+    let (lowest, highest) = dst::offsets_near(&tz, &from.naive_utc());
+    // A local time runs at itself minus an offset in between the
+    // two, so only local times in this range can land in the stretch.
+    let last = until.naive_utc() + highest;
+    let mut local = fields.after(&(from.naive_utc() + lowest - Duration::seconds(1)));
+    let mut datetimes = BTreeSet::new();
+    while let Some(current) = local.filter(|local| *local < last) {
+        datetimes.extend(
+            schedule
+                .dst
+                .instants(&tz, &current, lowest, highest)
+                .filter(|datetime| from <= datetime && datetime < until),
+        );
+        local = fields.after(&current);
+    }
+    datetimes
+}
+
+/// Drops anything under a second from `datetime`.
+pub(super) fn whole_seconds<Tz: TimeZone>(datetime: &DateTime<Tz>) -> DateTime<Tz> {
+    // `with_nanosecond` goes through the local time, which
+    // fails for the second pass through an ambiguous one.
+    datetime.clone() - Duration::nanoseconds(datetime.nanosecond().into())
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,161 @@
+use crate::schedule::{AmbiguousTime, DstPolicy, NonexistentTime, Schedule};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::America::Chicago;
+
+/// Counts the slow way, one datetime at a time.
+fn stepped<Tz: TimeZone + 'static>(
+    schedule: &Schedule,
+    start: &DateTime<Tz>,
+    end: &DateTime<Tz>,
+) -> u64 {
+    schedule
+        .iter_from(start)
+        .take_while(|datetime| datetime <= end)
+        .count() as u64
+}
+
+#[test]
+fn count_agrees_with_stepping() {
+    let start = Utc.with_ymd_and_hms(2023, 1, 30, 13, 16, 30).unwrap();
+    let end = Utc.with_ymd_and_hms(2023, 5, 2, 17, 0, 0).unwrap();
+    for expression in [
+        "0 16 13-18 * * Mon-Fri",
+        "*/7 * 9-10 * * *",
+        "0 0 0 L * *",
+        "0 0 12 15W,LW * ?",
+        "0 30 9 ? * 5L,Tue#2",
+        "0 0 12 1,15 * Fri",
+        "0 0 * 29 Feb *",
+        "0 0 0 * * * 2023",
+    ] {
+        let schedule: Schedule = expression.parse().unwrap();
+        assert_eq!(
+            stepped(&schedule, &start, &end),
+            schedule.count_between(&start, &end, Utc),
+            "{expression:?}"
+        );
+    }
+}
+
+#[test]
+fn count_is_after_start_up_to_end() {
+    let schedule: Schedule = "0 0 0 * * *".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2023, 7, 1, 0, 0, 0).unwrap();
+
+    assert_eq!(30, schedule.count_between(&start, &end, Utc));
+    assert_eq!(0, schedule.count_between(&end, &start, Utc));
+    assert_eq!(
+        31,
+        schedule.count_between(&(start - Duration::milliseconds(1)), &end, Utc)
+    );
+}
+
+#[test]
+fn count_every_second_of_a_year() {
+    let schedule: Schedule = "* * * * * *".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 12, 31, 23, 59, 59).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 12, 31, 23, 59, 59).unwrap();
+
+    assert_eq!(366 * 86_400, schedule.count_between(&start, &end, Utc));
+}
+
+#[test]
+fn count_follows_the_dst_policy() {
+    // Chicago skipped 2:00 through 2:59 AM on March 12th, 2023,
+    // and went through 1:00 through 1:59 AM twice on November 5th.
+    let start = Chicago.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let end = Chicago.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    for (nonexistent, ambiguous, expected) in [
+        (NonexistentTime::Skip, AmbiguousTime::Earliest, 4 * 365 - 2),
+        (
+            NonexistentTime::ShiftForward,
+            AmbiguousTime::Twice,
+            4 * 365 + 2,
+        ),
+        (
+            NonexistentTime::AtTransition,
+            AmbiguousTime::Latest,
+            4 * 365 - 1,
+        ),
+    ] {
+        let schedule = "0 0,30 1,2 * * *"
+            .parse::<Schedule>()
+            .unwrap()
+            .with_dst_policy(DstPolicy::new(nonexistent, ambiguous));
+        let count = schedule.count_between(&start, &end, Chicago);
+        assert_eq!(expected, count, "{nonexistent:?}, {ambiguous:?}");
+        assert_eq!(stepped(&schedule, &start, &end), count);
+    }
+}
+
+#[test]
+fn count_every_second_across_a_dst_change() {
+    let schedule: Schedule = "* * * * * *".parse().unwrap();
+    let start = Chicago.with_ymd_and_hms(2023, 3, 11, 0, 0, 0).unwrap();
+    let end = Chicago.with_ymd_and_hms(2023, 3, 14, 0, 0, 0).unwrap();
+
+    assert_eq!(
+        3 * 86_400 - 3_600,
+        schedule.count_between(&start, &end, Chicago)
+    );
+}
+
+#[test]
+fn nth_agrees_with_stepping() {
+    let start = Chicago.with_ymd_and_hms(2023, 1, 30, 13, 16, 30).unwrap();
+    for expression in [
+        "0 16 13-18 * * Mon-Fri",
+        "*/7 * 9-10 * * *",
+        "0 0,30 1,2 * * *",
+        "0 0 12 15W,LW * ?",
+        "0 0 0 29 Feb *",
+    ] {
+        let schedule: Schedule = expression.parse().unwrap();
+        let datetimes: Vec<_> = schedule.iter_from(&start).take(1_000).collect();
+        for n in [1, 2, 17, 250, 999, 1_000] {
+            assert_eq!(
+                datetimes.get(n - 1).cloned(),
+                schedule.nth_after(&start, n as u64),
+                "{expression:?}, {n}"
+            );
+        }
+    }
+}
+
+#[test]
+fn nth_far_into_the_future() {
+    let schedule: Schedule = "* * * * * *".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+
+    assert_eq!(
+        Some(start + Duration::days(3_650)),
+        schedule.nth_after(&start, 3_650 * 86_400)
+    );
+    assert_eq!(None, schedule.nth_after(&start, 0));
+}
+
+#[test]
+fn nth_ends_with_the_schedule() {
+    let schedule: Schedule = "0 0 12 1 1 * 2098-2099".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+
+    assert_eq!(
+        Some(Utc.with_ymd_and_hms(2099, 1, 1, 12, 0, 0).unwrap()),
+        schedule.nth_after(&start, 2)
+    );
+    assert_eq!(None, schedule.nth_after(&start, 3));
+}
+
+#[test]
+fn every_counts_whole_intervals() {
+    let schedule: Schedule = "@every 1h30m".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let end = start + Duration::hours(6);
+
+    assert_eq!(4, schedule.count_between(&start, &end, Utc));
+    assert_eq!(
+        Some(start + Duration::hours(6)),
+        schedule.nth_after(&start, 4)
+    );
+}
@@ -0,0 +1,235 @@
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, Offset, TimeZone, Timelike};
+
+/// What a schedule does with the local times that don't line
+/// up with exactly one instant. These show up whenever a
+/// timezone changes its UTC offset, like at the start and
+/// end of daylight saving time.
+///
+/// The default skips local times that don't exist, and
+/// runs local times that happen twice only the first time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DstPolicy {
+    pub nonexistent: NonexistentTime,
+    pub ambiguous: AmbiguousTime,
+}
+
+/// What to do with a local time that gets skipped when the
+/// clocks jump forward, like 2:30 AM on the day that daylight
+/// saving time starts in most of the United States.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NonexistentTime {
+    /// Don't run at all.
+    #[default]
+    Skip,
+    /// Run as if the clocks hadn't jumped yet, so 2:30 AM
+    /// turns into 3:30 AM after a jump of one hour.
+    ShiftForward,
+    /// Run right as the clocks jump, so 2:30 AM
+    /// turns into 3:00 AM after a jump of one hour.
+    AtTransition,
+}
+
+/// What to do with a local time that happens twice when the
+/// clocks fall back, like 1:30 AM on the day that daylight
+/// saving time ends in most of the United States.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AmbiguousTime {
+    /// Run once, the first time around.
+    #[default]
+    Earliest,
+    /// Run once, the second time around.
+    Latest,
+    /// Run both times.
+    Twice,
+}
+
+impl DstPolicy {
+    pub fn new(nonexistent: NonexistentTime, ambiguous: AmbiguousTime) -> Self {
+        Self {
+            nonexistent,
+            ambiguous,
+        }
+    }
+
+    /// Returns the first instant strictly after `date_time`
+    /// that one of the local times from `after` runs at.
+    ///
+    /// `after` should give back the first local time of the
+    /// schedule that comes strictly after the one it's handed.
+    ///
+    /// Local times don't map to instants in order around an
+    /// offset change. The second time through an ambiguous hour
+    /// comes after local times that are later on the clock,
+    /// and a shifted nonexistent time can land after the local
+    /// times right behind it. So the search starts early enough
+    /// to catch all of those, and keeps going until no later
+    /// local time could possibly beat the best instant so far.
+    pub(super) fn after<Tz: TimeZone>(
+        &self,
+        date_time: &DateTime<Tz>,
+        after: impl Fn(&NaiveDateTime) -> Option<NaiveDateTime>,
+    ) -> Option<DateTime<Tz>> {
+        let tz = date_time.timezone();
+        let utc = date_time.naive_utc();
+        let (lowest, highest) = offsets_near(&tz, &utc);
+
+        // An instant is its local time minus an offset between
+        // `lowest` and `highest`, so anything that runs after
+        // `date_time` has a local time after `utc + lowest`.
+        let mut start = utc + lowest;
+        let mut best: Option<DateTime<Tz>> = None;
+        while let Some(local) = after(&start) {
+            for instant in self.instants(&tz, &local, lowest, highest) {
+                if instant > *date_time && best.as_ref().is_none_or(|best| instant < *best) {
+                    best = Some(instant);
+                }
+            }
+            start = local;
+            if best
+                .as_ref()
+                .is_some_and(|best| start + Duration::seconds(1) - highest > best.naive_utc())
+            {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Returns the last instant strictly before `date_time`
+    /// that one of the local times from `before` runs at.
+    /// This is `after` run in reverse.
+    pub(super) fn before<Tz: TimeZone>(
+        &self,
+        date_time: &DateTime<Tz>,
+        before: impl Fn(&NaiveDateTime) -> Option<NaiveDateTime>,
+    ) -> Option<DateTime<Tz>> {
+        let tz = date_time.timezone();
+        let utc = date_time.naive_utc();
+        let (lowest, highest) = offsets_near(&tz, &utc);
+
+        let mut end = utc + highest;
+        let mut best: Option<DateTime<Tz>> = None;
+        while let Some(local) = before(&end) {
+            for instant in self.instants(&tz, &local, lowest, highest) {
+                if instant < *date_time && best.as_ref().is_none_or(|best| instant > *best) {
+                    best = Some(instant);
+                }
+            }
+            end = local;
+            if best
+                .as_ref()
+                .is_some_and(|best| end - Duration::seconds(1) - lowest < best.naive_utc())
+            {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Returns true if `date_time` is one of the instants that
+    /// the schedule runs at, ignoring anything under a second.
+    ///
+    /// Away from any offset change, that's the same as `contains`
+    /// saying yes to its local time. Close to one, a local time
+    /// can run at a different instant or not at all, so the
+    /// answer comes from looking for the next instant instead.
+    pub(super) fn matches<Tz: TimeZone>(
+        &self,
+        date_time: &DateTime<Tz>,
+        contains: impl Fn(&NaiveDateTime) -> bool,
+        after: impl Fn(&NaiveDateTime) -> Option<NaiveDateTime>,
+    ) -> bool {
+        // `with_nanosecond` goes through the local time, which
+        // fails for the second pass through an ambiguous one.
+        let date_time = date_time.clone() - Duration::nanoseconds(date_time.nanosecond().into());
+        let tz = date_time.timezone();
+        let (lowest, highest) = offsets_near(&tz, &date_time.naive_utc());
+        if lowest == highest {
+            contains(&date_time.naive_local())
+        } else {
+            let just_before = date_time.clone() - Duration::seconds(1);
+            self.after(&just_before, after) == Some(date_time)
+        }
+    }
+
+    /// Returns the instants that `local` runs at, which can
+    /// be none, one or two of them depending on the policy.
+    /// The offsets are the lowest and highest ones around
+    /// `local`, which bound where an offset change can be.
+    pub(super) fn instants<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+        local: &NaiveDateTime,
+        lowest: Duration,
+        highest: Duration,
+    ) -> impl Iterator<Item = DateTime<Tz>> {
+        let instants = match tz.from_local_datetime(local) {
+            LocalResult::Single(instant) => [Some(instant), None],
+            LocalResult::Ambiguous(earliest, latest) => match self.ambiguous {
+                AmbiguousTime::Earliest => [Some(earliest), None],
+                AmbiguousTime::Latest => [Some(latest), None],
+                AmbiguousTime::Twice => [Some(earliest), Some(latest)],
+            },
+            LocalResult::None => {
+                // The clocks jumped forward over `local`, so reading
+                // it with the highest offset lands before the jump,
+                // and reading it with the lowest one lands after.
+                let (before, after) = (*local - highest, *local - lowest);
+                let instant = match self.nonexistent {
+                    NonexistentTime::Skip => None,
+                    NonexistentTime::ShiftForward => Some(*local - offset_at(tz, &before)),
+                    NonexistentTime::AtTransition => Some(transition(tz, before, after)),
+                };
+                [instant.map(|instant| tz.from_utc_datetime(&instant)), None]
+            }
+        };
+        instants.into_iter().flatten()
+    }
+}
+
+/// Returns the UTC offset that `tz` uses at the `utc` instant.
+pub(super) fn offset_at<Tz: TimeZone>(tz: &Tz, utc: &NaiveDateTime) -> Duration {
+    Duration::seconds(tz.offset_from_utc_datetime(utc).fix().local_minus_utc() as i64)
+}
+
+/// Returns the lowest and highest UTC offsets that `tz` uses
+/// within a day of the `utc` instant. No offset change moves
+/// the clocks by more than a day, so every local time close
+/// to `utc` is read with one of the offsets in between.
+pub(super) fn offsets_near<Tz: TimeZone>(tz: &Tz, utc: &NaiveDateTime) -> (Duration, Duration) {
+    let day = Duration::days(1);
+    let here = offset_at(tz, utc);
+    [utc.checked_sub_signed(day), utc.checked_add_signed(day)]
+        .into_iter()
+        .flatten()
+        .map(|utc| offset_at(tz, &utc))
+        .fold((here, here), |(lowest, highest), offset| {
+            (lowest.min(offset), highest.max(offset))
+        })
+}
+
+/// Returns the first instant that `tz` uses a different offset at
+/// than it does at `before`, down to the second. Assumes that the
+/// offset changes exactly once on the way from `before` to `after`.
+pub(super) fn transition<Tz: TimeZone>(
+    tz: &Tz,
+    mut before: NaiveDateTime,
+    mut after: NaiveDateTime,
+) -> NaiveDateTime {
+    let offset = offset_at(tz, &before);
+    while after - before > Duration::seconds(1) {
+        let middle = before + (after - before) / 2;
+        if offset_at(tz, &middle) == offset {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+    after
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,211 @@
+use super::{AmbiguousTime, DstPolicy, NonexistentTime};
+use crate::schedule::Schedule;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::{America::Chicago, Australia::Lord_Howe, Tz};
+
+// In 2023, Chicago jumped from 2:00 AM CST to 3:00 AM CDT on
+// March 12th, and fell back from 2:00 AM CDT to 1:00 AM CST on
+// November 5th. Both changes happened at 8:00 and 7:00 UTC.
+
+fn with_policy(
+    expression: &str,
+    nonexistent: NonexistentTime,
+    ambiguous: AmbiguousTime,
+) -> Schedule {
+    expression
+        .parse::<Schedule>()
+        .unwrap()
+        .with_dst_policy(DstPolicy::new(nonexistent, ambiguous))
+}
+
+fn utc(month: u32, day: u32, hour: u32, min: u32) -> DateTime<Tz> {
+    Utc.with_ymd_and_hms(2023, month, day, hour, min, 0)
+        .unwrap()
+        .with_timezone(&Chicago)
+}
+
+fn chicago(month: u32, day: u32, hour: u32, min: u32) -> DateTime<Tz> {
+    Chicago
+        .with_ymd_and_hms(2023, month, day, hour, min, 0)
+        .single()
+        .unwrap()
+}
+
+#[test]
+fn nonexistent_times_no_longer_end_the_iterator() {
+    let schedule: Schedule = "0 30 2 * * *".parse().unwrap();
+    let start = chicago(3, 11, 12, 0);
+
+    let datetimes: Vec<_> = schedule.iter_from(&start).take(2).collect();
+    assert_eq!(
+        vec![chicago(3, 13, 2, 30), chicago(3, 14, 2, 30)],
+        datetimes
+    );
+}
+
+#[test]
+fn nonexistent_times_shift_forward() {
+    let schedule = with_policy(
+        "0 30 2 * * *",
+        NonexistentTime::ShiftForward,
+        AmbiguousTime::Earliest,
+    );
+    let start = chicago(3, 11, 12, 0);
+
+    let datetimes: Vec<_> = schedule.iter_from(&start).take(2).collect();
+    assert_eq!(
+        vec![chicago(3, 12, 3, 30), chicago(3, 13, 2, 30)],
+        datetimes
+    );
+}
+
+#[test]
+fn nonexistent_times_run_at_the_transition() {
+    let schedule = with_policy(
+        "0 30 2 * * *",
+        NonexistentTime::AtTransition,
+        AmbiguousTime::Earliest,
+    );
+    let start = chicago(3, 11, 12, 0);
+
+    let datetimes: Vec<_> = schedule.iter_from(&start).take(2).collect();
+    assert_eq!(vec![utc(3, 12, 8, 0), chicago(3, 13, 2, 30)], datetimes);
+    assert_eq!(chicago(3, 12, 3, 0), datetimes[0]);
+}
+
+#[test]
+fn shifted_times_are_not_repeated() {
+    let start = chicago(3, 12, 1, 0);
+    let expected = vec![
+        chicago(3, 12, 1, 30),
+        chicago(3, 12, 3, 0),
+        chicago(3, 12, 3, 30),
+        chicago(3, 12, 4, 0),
+    ];
+
+    for nonexistent in [NonexistentTime::ShiftForward, NonexistentTime::AtTransition] {
+        let schedule = with_policy("0 */30 * * * *", nonexistent, AmbiguousTime::Earliest);
+        let datetimes: Vec<_> = schedule.iter_from(&start).take(4).collect();
+        assert_eq!(expected, datetimes, "{nonexistent:?}");
+    }
+}
+
+#[test]
+fn shifted_times_are_found_after_the_jump() {
+    let schedule = with_policy(
+        "0 30 2 * * *",
+        NonexistentTime::ShiftForward,
+        AmbiguousTime::Earliest,
+    );
+    // 3:10 AM CDT, after the clocks jumped but before
+    // the shifted 2:30 AM has happened.
+    let start = chicago(3, 12, 3, 10);
+
+    assert_eq!(Some(chicago(3, 12, 3, 30)), schedule.after(&start));
+}
+
+#[test]
+fn ambiguous_times_run_once_or_twice() {
+    let start = chicago(11, 4, 12, 0);
+    let cases = [
+        (AmbiguousTime::Earliest, vec![utc(11, 5, 6, 30)]),
+        (AmbiguousTime::Latest, vec![utc(11, 5, 7, 30)]),
+        (
+            AmbiguousTime::Twice,
+            vec![utc(11, 5, 6, 30), utc(11, 5, 7, 30)],
+        ),
+    ];
+
+    for (ambiguous, mut expected) in cases {
+        let schedule = with_policy("0 30 1 * * *", NonexistentTime::Skip, ambiguous);
+        expected.push(chicago(11, 6, 1, 30));
+
+        let datetimes: Vec<_> = schedule.iter_from(&start).take(expected.len()).collect();
+        assert_eq!(expected, datetimes, "{ambiguous:?}");
+    }
+}
+
+#[test]
+fn running_twice_keeps_the_order_of_instants() {
+    let schedule = with_policy(
+        "0 */15 * * * *",
+        NonexistentTime::Skip,
+        AmbiguousTime::Twice,
+    );
+    let start = utc(11, 5, 5, 30);
+
+    let datetimes: Vec<_> = schedule.iter_from(&start).take(12).collect();
+    assert_eq!(utc(11, 5, 5, 45), datetimes[0]);
+    for pair in datetimes.windows(2) {
+        assert_eq!(Duration::minutes(15), pair[1] - pair[0]);
+    }
+}
+
+#[test]
+fn running_once_skips_the_repeated_hour() {
+    let start = chicago(11, 5, 0, 45);
+
+    let schedule = with_policy(
+        "0 */15 * * * *",
+        NonexistentTime::Skip,
+        AmbiguousTime::Earliest,
+    );
+    let datetimes: Vec<_> = schedule.iter_from(&start).take(5).collect();
+    assert_eq!(utc(11, 5, 6, 45), datetimes[3]);
+    assert_eq!(utc(11, 5, 8, 0), datetimes[4]);
+
+    let schedule = with_policy(
+        "0 */15 * * * *",
+        NonexistentTime::Skip,
+        AmbiguousTime::Latest,
+    );
+    let datetimes: Vec<_> = schedule.iter_from(&start).take(2).collect();
+    assert_eq!(vec![utc(11, 5, 7, 0), utc(11, 5, 7, 15)], datetimes);
+}
+
+#[test]
+fn backwards_is_the_reverse_of_forwards() {
+    let policies = [
+        (NonexistentTime::Skip, AmbiguousTime::Earliest),
+        (NonexistentTime::ShiftForward, AmbiguousTime::Latest),
+        (NonexistentTime::AtTransition, AmbiguousTime::Twice),
+    ];
+    for (start, end) in [
+        (chicago(3, 11, 22, 0), chicago(3, 12, 6, 0)),
+        (chicago(11, 4, 22, 0), chicago(11, 5, 6, 0)),
+    ] {
+        for (nonexistent, ambiguous) in policies {
+            let schedule = with_policy("0 0,20,40 * * * *", nonexistent, ambiguous);
+
+            let forwards: Vec<_> = schedule
+                .iter_from(&start)
+                .take_while(|datetime| *datetime < end)
+                .collect();
+            let mut backwards: Vec<_> = schedule
+                .iter_backwards_from(&end)
+                .take_while(|datetime| *datetime > start)
+                .collect();
+            backwards.reverse();
+            assert_eq!(forwards, backwards, "{nonexistent:?}, {ambiguous:?}");
+        }
+    }
+}
+
+#[test]
+fn half_hour_transitions() {
+    // Lord Howe Island only moves its clocks by half an hour,
+    // from 2:00 AM to 2:30 AM on October 1st, 2023.
+    let schedule = "0 15 2 * * *"
+        .parse::<Schedule>()
+        .unwrap()
+        .with_dst_policy(DstPolicy::new(
+            NonexistentTime::ShiftForward,
+            AmbiguousTime::Earliest,
+        ));
+    let start = Lord_Howe.with_ymd_and_hms(2023, 9, 30, 12, 0, 0).unwrap();
+
+    assert_eq!(
+        Some(Lord_Howe.with_ymd_and_hms(2023, 10, 1, 2, 45, 0).unwrap()),
+        schedule.after(&start)
+    );
+}
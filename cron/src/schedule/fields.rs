@@ -1,15 +1,38 @@
-use self::inner::{Days, Hours, Minutes, Months, Seconds};
-use super::iterator::CopyRing;
-use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+pub use self::inner::{MonthDayModifier, WeekdayModifier};
+use self::{
+    bits::{Bits, Word},
+    inner::{Days, Hours, Minutes, MonthDays, Months, Seconds, WeekDays, Years},
+};
+use crate::Field;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use std::fmt;
 
 #[derive(Clone, Debug)]
 pub enum Error {
-    MissingField,
-    EmptyRing,
-    OutOfRange,
+    MissingField(Field),
+    EmptyRing(Field),
+    OutOfRange(Field),
+    NeverRuns,
 }
 
+impl From<Error> for crate::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::MissingField(field) => crate::Error::MissingField(field),
+            Error::EmptyRing(field) => crate::Error::EmptyField(field),
+            Error::OutOfRange(field) => crate::Error::FieldOutOfRange(field),
+            Error::NeverRuns => crate::Error::NeverRuns,
+        }
+    }
+}
+
+mod bits;
+mod compare;
+mod count;
+mod describe;
+mod format;
 mod inner;
+mod rrule;
 
 #[derive(Clone, Debug)]
 pub struct FieldTable {
@@ -18,19 +41,45 @@ pub struct FieldTable {
     hours: Hours,
     days: Days,
     months: Months,
+    years: Years,
+}
+
+/// The number of years it takes for the Gregorian calendar
+/// to repeat itself, weekdays and leap years included. If a
+/// search goes this far without finding anything, then it
+/// never will.
+const YEARS_IN_A_CYCLE: u32 = 400;
+
+/// The earliest and latest years that can be given to
+/// the year field.
+pub(super) const FIRST_YEAR: u32 = 1970;
+pub(super) const LAST_YEAR: u32 = 2099;
+
+/// Returns the first and last moments of the years that
+/// a schedule can run in, which every search stays inside.
+fn year_bounds() -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let first = NaiveDate::from_ymd_opt(FIRST_YEAR as i32, 1, 1)?.and_hms_opt(0, 0, 0)?;
+    let last = NaiveDate::from_ymd_opt(LAST_YEAR as i32, 12, 31)?.and_hms_opt(23, 59, 59)?;
+    Some((first, last))
+}
+
+/// Returns the year of `date`, or `None` if a
+/// schedule can't run in it.
+fn year_of(date: &impl Datelike) -> Option<u32> {
+    u32::try_from(date.year())
+        .ok()
+        .filter(|year| (FIRST_YEAR..=LAST_YEAR).contains(year))
 }
 
 /// A builder pattern for the `FieldTable`.
 /// To build a `FieldTable`, supply the builder
 /// with values for each of fields.
 ///
-/// Each method accepts an item that impl's
-/// `Into<CopyRing<u8>>`, which is any of the
-/// following:
-/// - a single value `u8`
-/// - any struct that impl's `IntoIterator<Item = u8>`
-/// - a `Vec<u8>` itself
-///     
+/// Each field can be given either a single value, or with
+/// the `_iter` methods, anything that impl's
+/// `IntoIterator<Item = u8>`. The values don't need to be
+/// sorted, and repeats are fine.
+///
 /// The only field that behaves differently is the `Day` field.
 /// For the days, choose one of the
 /// methods `with_days_of_the_month_only`,
@@ -39,85 +88,95 @@ pub struct FieldTable {
 /// then `with_days_of_the_month_only` should be used and
 /// a full iterator of 1..=31 should be supplied.
 ///
+/// Days that depend on the month they land in, like the
+/// last Friday of the month, go in through
+/// `with_days_of_the_month_modifiers` and
+/// `with_days_of_the_week_modifiers`, on top of whichever
+/// day method was used. A part of the day field with
+/// modifiers doesn't need any plain days.
+///
+/// The years are the only optional field. Without them,
+/// the schedule keeps going forever.
+///
 /// # Failure
 ///
 /// All fields should be supplied, or the build fails
-/// with an `Error::MissingField`.
+/// with an `Error::MissingField`. Each error names the
+/// first field that was found to be wrong, going from
+/// the seconds up to the years.
 /// Each field should contain values that are
 /// within bounds for that field, or else
 /// the build will fail with an `Error::OutOfRange`.
 /// Likewise, a field should not be empty, or else
 /// the build will fail with an `Error::EmptyRing`.
-///
-/// # Assumptions
-///
-/// The main assumption that will not be tested is that
-/// the supplied field values are sorted, and that
-/// the first value is the lowest in the range.
+/// Fields that are fine on their own, but that never
+/// line up into a real date, like the 30th of February,
+/// fail with an `Error::NeverRuns`.
 #[derive(Default)]
 pub struct Builder {
-    secs: Option<CopyRing<u8>>,
-    mins: Option<CopyRing<u8>>,
-    hrs: Option<CopyRing<u8>>,
-    days: Option<Days>,
-    months: Option<CopyRing<u8>>,
+    secs: Option<Vec<u8>>,
+    mins: Option<Vec<u8>>,
+    hrs: Option<Vec<u8>>,
+    month_days: Option<Vec<u8>>,
+    week_days: Option<Vec<u8>>,
+    month_modifiers: Vec<MonthDayModifier>,
+    week_modifiers: Vec<WeekdayModifier>,
+    months: Option<Vec<u8>>,
+    years: Option<Vec<u32>>,
 }
 
 impl Builder {
     pub fn with_secs_iter(&mut self, secs: impl IntoIterator<Item = u8>) -> &mut Self {
-        self.secs = Some(CopyRing::from_iter(secs));
+        self.secs = Some(secs.into_iter().collect());
         self
     }
 
-    pub fn with_secs(&mut self, secs: impl Into<CopyRing<u8>>) -> &mut Self {
-        self.secs = Some(secs.into());
-        self
+    pub fn with_secs(&mut self, secs: u8) -> &mut Self {
+        self.with_secs_iter([secs])
     }
 
     pub fn with_mins_iter(&mut self, mins: impl IntoIterator<Item = u8>) -> &mut Self {
-        self.mins = Some(CopyRing::from_iter(mins));
+        self.mins = Some(mins.into_iter().collect());
         self
     }
 
-    pub fn with_mins(&mut self, mins: impl Into<CopyRing<u8>>) -> &mut Self {
-        self.mins = Some(mins.into());
-        self
+    pub fn with_mins(&mut self, mins: u8) -> &mut Self {
+        self.with_mins_iter([mins])
     }
 
     pub fn with_hrs_iter(&mut self, hrs: impl IntoIterator<Item = u8>) -> &mut Self {
-        self.hrs = Some(CopyRing::from_iter(hrs));
+        self.hrs = Some(hrs.into_iter().collect());
         self
     }
 
-    pub fn with_hrs(&mut self, hrs: impl Into<CopyRing<u8>>) -> &mut Self {
-        self.mins = Some(hrs.into());
-        self
+    pub fn with_hrs(&mut self, hrs: u8) -> &mut Self {
+        self.with_hrs_iter([hrs])
     }
 
     pub fn with_days_of_the_month_only_iter(
         &mut self,
         days: impl IntoIterator<Item = u8>,
     ) -> &mut Self {
-        self.days = Some(Days::Month(CopyRing::from_iter(days)));
+        self.month_days = Some(days.into_iter().collect());
+        self.week_days = None;
         self
     }
 
-    pub fn with_days_of_the_month_only(&mut self, days: impl Into<CopyRing<u8>>) -> &mut Self {
-        self.days = Some(Days::Month(days.into()));
-        self
+    pub fn with_days_of_the_month_only(&mut self, days: u8) -> &mut Self {
+        self.with_days_of_the_month_only_iter([days])
     }
 
     pub fn with_days_of_the_week_only_iter(
         &mut self,
         days: impl IntoIterator<Item = u8>,
     ) -> &mut Self {
-        self.days = Some(Days::Week((CopyRing::from_iter(days), Default::default())));
+        self.month_days = None;
+        self.week_days = Some(days.into_iter().collect());
         self
     }
 
-    pub fn with_days_of_the_week_only(&mut self, days: impl Into<CopyRing<u8>>) -> &mut Self {
-        self.days = Some(Days::Week((days.into(), Default::default())));
-        self
+    pub fn with_days_of_the_week_only(&mut self, days: u8) -> &mut Self {
+        self.with_days_of_the_week_only_iter([days])
     }
 
     pub fn with_days_of_both_iter(
@@ -125,130 +184,372 @@ impl Builder {
         week: impl IntoIterator<Item = u8>,
         month: impl IntoIterator<Item = u8>,
     ) -> &mut Self {
-        self.days = Some(Days::Both {
-            month: CopyRing::from_iter(month),
-            week: (CopyRing::from_iter(week), Default::default()),
-        });
+        self.month_days = Some(month.into_iter().collect());
+        self.week_days = Some(week.into_iter().collect());
         self
     }
 
-    pub fn with_days_of_both(
+    #[allow(dead_code)]
+    pub fn with_days_of_both(&mut self, week: u8, month: u8) -> &mut Self {
+        self.with_days_of_both_iter([week], [month])
+    }
+
+    pub fn with_days_of_the_month_modifiers(
         &mut self,
-        week: impl Into<CopyRing<u8>>,
-        month: impl Into<CopyRing<u8>>,
+        modifiers: impl IntoIterator<Item = MonthDayModifier>,
     ) -> &mut Self {
-        self.days = Some(Days::Both {
-            month: month.into(),
-            week: (week.into(), Default::default()),
-        });
+        self.month_modifiers = modifiers.into_iter().collect();
+        self
+    }
+
+    pub fn with_days_of_the_week_modifiers(
+        &mut self,
+        modifiers: impl IntoIterator<Item = WeekdayModifier>,
+    ) -> &mut Self {
+        self.week_modifiers = modifiers.into_iter().collect();
         self
     }
 
     pub fn with_months_iter(&mut self, months: impl IntoIterator<Item = u8>) -> &mut Self {
-        self.months = Some(CopyRing::from_iter(months));
+        self.months = Some(months.into_iter().collect());
         self
     }
 
-    pub fn with_months(&mut self, months: impl Into<CopyRing<u8>>) -> &mut Self {
-        self.mins = Some(months.into());
+    pub fn with_months(&mut self, months: u8) -> &mut Self {
+        self.with_months_iter([months])
+    }
+
+    pub fn with_years_iter(&mut self, years: impl IntoIterator<Item = u32>) -> &mut Self {
+        self.years = Some(years.into_iter().collect());
         self
     }
 
     pub fn build(&mut self) -> Result<FieldTable, Error> {
-        if self.secs.is_none()
-            || self.mins.is_none()
-            || self.hrs.is_none()
-            || self.days.is_none()
-            || self.months.is_none()
-        {
-            return Err(Error::MissingField);
-        }
+        let secs = self
+            .secs
+            .take()
+            .ok_or(Error::MissingField(Field::Seconds))?;
+        let mins = self
+            .mins
+            .take()
+            .ok_or(Error::MissingField(Field::Minutes))?;
+        let hrs = self.hrs.take().ok_or(Error::MissingField(Field::Hours))?;
+        let (month_days, week_days) = (self.month_days.take(), self.week_days.take());
+        let months = self
+            .months
+            .take()
+            .ok_or(Error::MissingField(Field::Months))?;
+        let years = self.years.take();
 
-        let secs = self.secs.take().unwrap();
-        let mins = self.mins.take().unwrap();
-        let hrs = self.hrs.take().unwrap();
-        let days = self.days.take().unwrap();
-        let months = self.months.take().unwrap();
-
-        if secs.is_empty()
-            || mins.is_empty()
-            || hrs.is_empty()
-            || match days {
-                Days::Both {
-                    ref month,
-                    ref week,
-                } => month.is_empty() || week.0.is_empty(),
-                Days::Month(ref month) => month.is_empty(),
-                Days::Week(ref week) => week.0.is_empty(),
-            }
-            || months.is_empty()
+        // Values that don't fit in the bits would just disappear,
+        // so they have to be caught before getting that far.
+        let in_range = |field: Field, values: &[u8], min: u8, max: u8| match values
+            .iter()
+            .all(|value| (min..=max).contains(value))
         {
-            return Err(Error::EmptyRing);
+            true => Ok(()),
+            false => Err(Error::OutOfRange(field)),
+        };
+        let check = |field: Field, values: &[u8], min: u8, max: u8| {
+            in_range(field, values, min, max)?;
+            match values.is_empty() {
+                true => Err(Error::EmptyRing(field)),
+                false => Ok(()),
+            }
+        };
+        check(Field::Seconds, &secs, 0, 59)?;
+        check(Field::Minutes, &mins, 0, 59)?;
+        check(Field::Hours, &hrs, 0, 23)?;
+
+        if let Some(ref month) = month_days {
+            in_range(Field::DaysOfMonth, month, 1, 31)?;
         }
+        if let Some(ref week) = week_days {
+            in_range(Field::DaysOfWeek, week, 0, 6)?;
+        }
+        let mut days = match (month_days, week_days) {
+            (Some(month), Some(week)) => Days::Both {
+                month: MonthDays::from(bits(month)),
+                week: WeekDays::from(bits(week)),
+            },
+            (Some(month), None) => Days::Month(bits(month).into()),
+            (None, Some(week)) => Days::Week(bits(week).into()),
+            (None, None) => return Err(Error::MissingField(Field::DaysOfMonth)),
+        };
+        let month_modifiers = std::mem::take(&mut self.month_modifiers);
+        let week_modifiers = std::mem::take(&mut self.week_modifiers);
+        days.set_modifiers(month_modifiers, week_modifiers)?;
+        days.check()?;
 
-        if secs.last().unwrap() >= 60
-            || mins.last().unwrap() >= 60
-            || hrs.last().unwrap() >= 24
-            || match days {
-                Days::Both {
-                    ref month,
-                    ref week,
-                } => {
-                    month.last().unwrap() > 31
-                        || month.first().unwrap() < 1
-                        || week.0.last().unwrap() >= 7
+        check(Field::Months, &months, 1, 12)?;
+        if let Some(ref years) = years {
+            match (years.iter().min(), years.iter().max()) {
+                (Some(&first), Some(&last)) if first < FIRST_YEAR || last > LAST_YEAR => {
+                    return Err(Error::OutOfRange(Field::Years))
                 }
-                Days::Month(ref month) => month.last().unwrap() > 31 || month.first().unwrap() < 1,
-                Days::Week(ref week) => week.0.last().unwrap() >= 7,
+                (Some(_), Some(_)) => {}
+                _ => return Err(Error::EmptyRing(Field::Years)),
             }
-            || months.last().unwrap() > 12
-            || months.first().unwrap() < 1
-        {
-            return Err(Error::OutOfRange);
         }
 
-        Ok(FieldTable {
-            secs: Seconds::new(secs),
-            mins: Minutes::new(mins),
-            hours: Hours::new(hrs),
+        let table = FieldTable {
+            secs: Seconds::new(bits(secs)),
+            mins: Minutes::new(bits(mins)),
+            hours: Hours::new(bits(hrs)),
             days,
-            months: Months::new(months),
-        })
+            months: Months::new(bits(months)),
+            years: years.map(Years::new).unwrap_or_default(),
+        };
+        match table.is_satisfiable() {
+            true => Ok(table),
+            false => Err(Error::NeverRuns),
+        }
     }
 }
 
+fn bits<T: Word>(values: Vec<u8>) -> Bits<T> {
+    values.into_iter().collect()
+}
+
 impl FieldTable {
-    pub fn after<Tz: TimeZone + 'static>(
-        &mut self,
-        date_time: &DateTime<Tz>,
-    ) -> Option<NaiveDateTime> {
-        let (sec, overflow) = self.secs.first_after(date_time.second() as u8);
-        let (min, overflow) = self.mins.first_after(date_time.minute() as u8, overflow);
-        let (hour, overflow) = self.hours.first_after(date_time.hour() as u8, overflow);
-        let (day, overflow) = self.days.first_after(
-            date_time.day() as u8,
-            date_time.weekday().num_days_from_sunday() as u8,
-            overflow,
-            date_time.month() as u8,
-            date_time.year() as u32,
+    /// Returns the first local datetime that fits
+    /// the table and comes strictly after `date_time`.
+    ///
+    /// Returns `None` if there are no datetimes left, either
+    /// because the last year of the schedule has passed, or
+    /// because the table describes days that never happen.
+    pub fn after(&self, date_time: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = date_time.with_nanosecond(0)? + Duration::seconds(1);
+        self.first_from(&start)
+    }
+
+    /// Returns the last local datetime that fits
+    /// the table and comes strictly before `date_time`.
+    ///
+    /// Returns `None` if there are no datetimes left, either
+    /// because the first year of the schedule hasn't come yet,
+    /// or because the table describes days that never happen.
+    pub fn before(&self, date_time: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let end = date_time.with_nanosecond(0)?;
+        if date_time.nanosecond() > 0 {
+            self.last_until(&end)
+        } else {
+            self.last_until(&(end - Duration::seconds(1)))
+        }
+    }
+
+    /// Returns true if every field of `date_time` is one of the
+    /// values in the table. Anything under a second is ignored.
+    pub fn contains(&self, date_time: &NaiveDateTime) -> bool {
+        let Some(year) = year_of(date_time) else {
+            return false;
+        };
+        let (month, day) = (date_time.month() as u8, date_time.day() as u8);
+        self.years.contains(year)
+            && self.months.contains(month)
+            && self.days.contains(day, month, year)
+            && self.hours.contains(date_time.hour() as u8)
+            && self.mins.contains(date_time.minute() as u8)
+            && self.secs.contains(date_time.second() as u8)
+    }
+
+    /// Returns true if the table runs at least once. Every field
+    /// has something in it, so this comes down to whether any of
+    /// the days land in any of the months, in any of the years.
+    ///
+    /// The calendar repeats itself every `YEARS_IN_A_CYCLE`
+    /// years, so a table that doesn't run in one cycle, starting
+    /// from the earliest year it could run in, never runs at all.
+    pub fn is_satisfiable(&self) -> bool {
+        let mut year = FIRST_YEAR;
+        while let Some(next) = self.years.first_from(year) {
+            if next >= FIRST_YEAR + YEARS_IN_A_CYCLE {
+                break;
+            }
+            let runs = (1..=12)
+                .filter(|&month| self.months.contains(month))
+                .any(|month| !self.days.in_month(month, next).is_empty());
+            if runs {
+                return true;
+            }
+            year = next + 1;
+        }
+        false
+    }
+
+    /// Returns the first datetime that fits the table and is
+    /// equal to or later than `start`.
+    ///
+    /// Starting from the year and working down to the seconds,
+    /// each field is moved forward to its next allowed value. Moving
+    /// a field forward resets every field below it back to its lowest
+    /// value, and a field that runs out of values moves the field above
+    /// it forward by one instead, before starting over from the year.
+    ///
+    /// A start before `FIRST_YEAR` starts from its first moment, and
+    /// nothing comes after `LAST_YEAR`.
+    fn first_from(&self, start: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let (first, last) = year_bounds()?;
+        if *start > last {
+            return None;
+        }
+        let start = (*start).max(first);
+        let mut year = year_of(&start)?;
+        let (mut month, mut day) = (start.month() as u8, start.day() as u8);
+        let (mut hour, mut min, mut sec) = (
+            start.hour() as u8,
+            start.minute() as u8,
+            start.second() as u8,
         );
-        let (month, overflow) = self.months.first_after(overflow, date_time.month() as u8);
-        let year = date_time.year() + overflow as i32;
 
-        NaiveDate::from_ymd_opt(year, month as u32, day as u32)
-            .and_then(|date| date.and_hms_opt(hour as u32, min as u32, sec as u32))
+        loop {
+            if year > LAST_YEAR {
+                return None;
+            }
+
+            match self.years.first_from(year)? {
+                next if next == year => {}
+                next => (year, month, day, hour, min, sec) = (next, 1, 1, 0, 0, 0),
+            }
+
+            match self.months.first_from(month) {
+                Some(next) if next == month => {}
+                Some(next) => (month, day, hour, min, sec) = (next, 1, 0, 0, 0),
+                None => {
+                    (year, month, day, hour, min, sec) = (year + 1, 1, 1, 0, 0, 0);
+                    continue;
+                }
+            }
+
+            match self.days.first_from(day, month, year) {
+                Some(next) if next == day => {}
+                Some(next) => (day, hour, min, sec) = (next, 0, 0, 0),
+                None => {
+                    (month, day, hour, min, sec) = (month + 1, 1, 0, 0, 0);
+                    continue;
+                }
+            }
+
+            match self.hours.first_from(hour) {
+                Some(next) if next == hour => {}
+                Some(next) => (hour, min, sec) = (next, 0, 0),
+                None => {
+                    (day, hour, min, sec) = (day + 1, 0, 0, 0);
+                    continue;
+                }
+            }
+
+            match self.mins.first_from(min) {
+                Some(next) if next == min => {}
+                Some(next) => (min, sec) = (next, 0),
+                None => {
+                    (hour, min, sec) = (hour + 1, 0, 0);
+                    continue;
+                }
+            }
+
+            match self.secs.first_from(sec) {
+                Some(next) => sec = next,
+                None => {
+                    (min, sec) = (min + 1, 0);
+                    continue;
+                }
+            }
+
+            return NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+                .and_then(|date| date.and_hms_opt(hour as u32, min as u32, sec as u32));
+        }
     }
 
-    pub fn next(&mut self, curr_month: u8, curr_year: u32) -> Option<NaiveDateTime> {
-        let (sec, overflow) = self.secs.next();
-        let (min, overflow) = self.mins.next(overflow);
-        let (hour, overflow) = self.hours.next(overflow);
-        let (day, overflow) = self.days.next(overflow, curr_month, curr_year);
-        let (month, overflow) = self.months.next(overflow);
-        let year = curr_year + overflow as u32;
+    /// Returns the last datetime that fits the table and is
+    /// equal to or earlier than `end`.
+    ///
+    /// This is `first_from` run in reverse. Each field is moved
+    /// back to its previous allowed value, which sets every field
+    /// below it to its highest value, and a field that runs out
+    /// of values moves the field above it back by one instead.
+    ///
+    /// An end after `LAST_YEAR` ends at its last moment, and
+    /// nothing comes before `FIRST_YEAR`.
+    fn last_until(&self, end: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let (first, last) = year_bounds()?;
+        if *end < first {
+            return None;
+        }
+        let end = (*end).min(last);
+        let mut year = year_of(&end)?;
+        let (mut month, mut day) = (end.month() as u8, end.day() as u8);
+        let (mut hour, mut min, mut sec) =
+            (end.hour() as u8, end.minute() as u8, end.second() as u8);
 
-        NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
-            .and_then(|date| date.and_hms_opt(hour as u32, min as u32, sec as u32))
+        loop {
+            if year < FIRST_YEAR {
+                return None;
+            }
+
+            match self.years.last_until(year)? {
+                prev if prev == year => {}
+                prev => (year, month, day, hour, min, sec) = (prev, 12, 31, 23, 59, 59),
+            }
+
+            // Days and months count from 1, so moving back
+            // from the first one lands on 0, which never matches.
+            match self.months.last_until(month) {
+                Some(prev) if prev == month => {}
+                Some(prev) => (month, day, hour, min, sec) = (prev, 31, 23, 59, 59),
+                None => {
+                    (year, month, day, hour, min, sec) = (year - 1, 12, 31, 23, 59, 59);
+                    continue;
+                }
+            }
+
+            match self.days.last_until(day, month, year) {
+                Some(prev) if prev == day => {}
+                Some(prev) => (day, hour, min, sec) = (prev, 23, 59, 59),
+                None => {
+                    (month, day, hour, min, sec) = (month - 1, 31, 23, 59, 59);
+                    continue;
+                }
+            }
+
+            match self.hours.last_until(hour) {
+                Some(prev) if prev == hour => {}
+                Some(prev) => (hour, min, sec) = (prev, 59, 59),
+                None => {
+                    (day, hour, min, sec) = (day - 1, 23, 59, 59);
+                    continue;
+                }
+            }
+
+            // The time of day counts from 0, so running out
+            // at midnight has to go back to the day before.
+            match self.mins.last_until(min) {
+                Some(prev) if prev == min => {}
+                Some(prev) => (min, sec) = (prev, 59),
+                None => {
+                    match hour.checked_sub(1) {
+                        Some(prev) => (hour, min, sec) = (prev, 59, 59),
+                        None => (day, hour, min, sec) = (day - 1, 23, 59, 59),
+                    }
+                    continue;
+                }
+            }
+
+            match self.secs.last_until(sec) {
+                Some(prev) => sec = prev,
+                None => {
+                    match (min.checked_sub(1), hour.checked_sub(1)) {
+                        (Some(prev), _) => (min, sec) = (prev, 59),
+                        (None, Some(prev)) => (hour, min, sec) = (prev, 59, 59),
+                        (None, None) => (day, hour, min, sec) = (day - 1, 23, 59, 59),
+                    }
+                    continue;
+                }
+            }
+
+            return NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+                .and_then(|date| date.and_hms_opt(hour as u32, min as u32, sec as u32));
+        }
     }
 
     pub fn builder() -> Builder {
@@ -256,5 +557,23 @@ impl FieldTable {
     }
 }
 
+/// Writes the table out as a six-field expression, or a
+/// seven-field one when the years are restricted, which
+/// parses back to a table that lands on the same datetimes.
+impl fmt::Display for FieldTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (days_of_month, days_of_week) = self.days.to_fields()?;
+        write!(
+            f,
+            "{} {} {} {days_of_month} {} {days_of_week}",
+            self.secs, self.mins, self.hours, self.months
+        )?;
+        match self.years.to_string() {
+            years if years.is_empty() => Ok(()),
+            years => write!(f, " {years}"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test;
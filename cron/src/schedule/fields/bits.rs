@@ -0,0 +1,164 @@
+use std::fmt;
+
+/// The unsigned integers that a `Bits` can be kept in.
+pub(super) trait Word: Copy + Default + Eq {
+    const BITS: u32;
+
+    fn widen(self) -> u64;
+    fn narrow(bits: u64) -> Self;
+}
+
+macro_rules! words {
+    ($($word:ty),*) => {
+        $(
+            impl Word for $word {
+                const BITS: u32 = <$word>::BITS;
+
+                fn widen(self) -> u64 {
+                    self as u64
+                }
+
+                fn narrow(bits: u64) -> Self {
+                    bits as $word
+                }
+            }
+        )*
+    };
+}
+
+words!(u8, u16, u32, u64);
+
+/// A set of small numbers, kept as one bit per number in
+/// whichever integer is just wide enough for the field:
+/// a `u64` for the seconds and minutes, a `u32` for the
+/// hours and days of the month, a `u16` for the months
+/// and a `u8` for the days of the week.
+///
+/// Finding the next value in the set is a single bit scan,
+/// and nothing about the set changes while searching it.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct Bits<T: Word>(T);
+
+impl<T: Word> Bits<T> {
+    /// Returns true if `value` can be kept in the set.
+    pub fn fits(value: u8) -> bool {
+        (value as u32) < T::BITS
+    }
+
+    /// Adds `value` to the set. Values that don't fit are
+    /// ignored, so the builder checks them beforehand.
+    pub fn insert(&mut self, value: u8) {
+        if Self::fits(value) {
+            self.0 = T::narrow(self.0.widen() | 1 << value);
+        }
+    }
+
+    pub fn contains(self, value: u8) -> bool {
+        Self::fits(value) && self.0.widen() & 1 << value != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == T::default()
+    }
+
+    /// Returns how many values are in the set.
+    pub fn len(self) -> u32 {
+        self.0.widen().count_ones()
+    }
+
+    /// Returns how many values in the set are
+    /// less than `value`.
+    pub fn count_below(self, value: u8) -> u32 {
+        match value {
+            64.. => self.len(),
+            value => (self.0.widen() & ((1 << value) - 1)).count_ones(),
+        }
+    }
+
+    /// Returns the smallest value in the set that is
+    /// equal to or greater than `value`.
+    pub fn first_from(self, value: u8) -> Option<u8> {
+        if !Self::fits(value) {
+            return None;
+        }
+        let bits = self.0.widen() & !0 << value;
+        (bits != 0).then(|| bits.trailing_zeros() as u8)
+    }
+
+    /// Returns the largest value in the set that is
+    /// equal to or less than `value`.
+    pub fn last_until(self, value: u8) -> Option<u8> {
+        let bits = match value {
+            63.. => self.0.widen(),
+            value => self.0.widen() & ((1 << (value + 1)) - 1),
+        };
+        (bits != 0).then(|| 63 - bits.leading_zeros() as u8)
+    }
+
+    /// Keeps only the values that are also in `other`.
+    pub fn intersection(self, other: Self) -> Self {
+        Self(T::narrow(self.0.widen() & other.0.widen()))
+    }
+
+    /// Returns every value in the set, smallest first.
+    pub fn iter(self) -> impl Iterator<Item = u8> {
+        let mut bits = self.0.widen();
+        std::iter::from_fn(move || {
+            let value = (bits != 0).then(|| bits.trailing_zeros() as u8)?;
+            bits &= bits - 1;
+            Some(value)
+        })
+    }
+
+    /// Returns the set of every value from `first` through
+    /// `last`, including both, as long as they fit.
+    pub fn range(first: u8, last: u8) -> Self {
+        match Self::fits(first) && first <= last {
+            true => {
+                let last = last.min(T::BITS as u8 - 1);
+                let through_last = u64::MAX >> (63 - last);
+                Self(T::narrow(through_last & !0 << first))
+            }
+            false => Self::default(),
+        }
+    }
+
+    /// Returns true if every value in the set is also in `other`.
+    pub fn is_subset_of(self, other: Self) -> bool {
+        self.intersection(other) == self
+    }
+
+    /// Adds every value in `other` to the set.
+    pub fn union(self, other: Self) -> Self {
+        Self(T::narrow(self.0.widen() | other.0.widen()))
+    }
+
+    /// Moves every value in the set up by `by`,
+    /// dropping the ones that no longer fit.
+    pub fn shifted_up(self, by: u8) -> Self {
+        match Self::fits(by) {
+            true => Self(T::narrow(self.0.widen() << by)),
+            false => Self::default(),
+        }
+    }
+}
+
+impl<T: Word> FromIterator<u8> for Bits<T> {
+    fn from_iter<I: IntoIterator<Item = u8>>(values: I) -> Self {
+        let mut bits = Self::default();
+        for value in values {
+            bits.insert(value);
+        }
+        bits
+    }
+}
+
+/// Shows the values in the set rather than the raw bits.
+impl<T: Word> fmt::Debug for Bits<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,92 @@
+use super::Bits;
+
+#[test]
+fn first_from_finds_the_next_value() {
+    let bits: Bits<u64> = [0, 15, 30, 59].into_iter().collect();
+    assert_eq!(Some(0), bits.first_from(0));
+    assert_eq!(Some(15), bits.first_from(1));
+    assert_eq!(Some(30), bits.first_from(30));
+    assert_eq!(Some(59), bits.first_from(31));
+    assert_eq!(None, bits.first_from(64));
+    assert_eq!(None, Bits::<u64>::default().first_from(0));
+}
+
+#[test]
+fn last_until_finds_the_previous_value() {
+    let bits: Bits<u32> = [1, 15, 31].into_iter().collect();
+    assert_eq!(None, bits.last_until(0));
+    assert_eq!(Some(1), bits.last_until(14));
+    assert_eq!(Some(15), bits.last_until(15));
+    assert_eq!(Some(31), bits.last_until(31));
+    assert_eq!(Some(31), bits.last_until(u8::MAX));
+}
+
+#[test]
+fn values_that_dont_fit_are_left_out() {
+    let bits: Bits<u8> = [0, 6, 8, 200].into_iter().collect();
+    assert_eq!(vec![0, 6], bits.iter().collect::<Vec<_>>());
+    assert!(!bits.contains(8));
+}
+
+#[test]
+fn iter_goes_from_smallest_to_largest() {
+    let bits: Bits<u16> = [12, 1, 6, 6].into_iter().collect();
+    assert_eq!(vec![1, 6, 12], bits.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn shifted_up_drops_the_overflow() {
+    // Every seventh day, moved to start on the 3rd.
+    let bits: Bits<u32> = Bits::<u32>::range(0, 31)
+        .iter()
+        .filter(|day| day % 7 == 0)
+        .collect();
+    assert_eq!(
+        vec![3, 10, 17, 24, 31],
+        bits.shifted_up(3).iter().collect::<Vec<_>>()
+    );
+    assert!(bits.shifted_up(32).is_empty());
+}
+
+#[test]
+fn union_and_intersection() {
+    let a: Bits<u8> = Bits::range(1, 5);
+    let b: Bits<u8> = [0, 5, 6].into_iter().collect();
+    assert_eq!(
+        vec![0, 1, 2, 3, 4, 5, 6],
+        a.union(b).iter().collect::<Vec<_>>()
+    );
+    assert_eq!(vec![5], a.intersection(b).iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn range_includes_both_ends() {
+    assert_eq!(
+        vec![1, 2, 3],
+        Bits::<u32>::range(1, 3).iter().collect::<Vec<_>>()
+    );
+    assert_eq!(63, Bits::<u64>::range(1, u8::MAX).iter().count());
+    assert_eq!(8, Bits::<u8>::range(0, 7).iter().count());
+    assert!(Bits::<u16>::range(5, 4).is_empty());
+    assert!(Bits::<u16>::range(16, 20).is_empty());
+}
+
+#[test]
+fn count_below_only_counts_smaller_values() {
+    let bits: Bits<u64> = [0, 15, 30, 59].into_iter().collect();
+    assert_eq!(4, bits.len());
+    assert_eq!(0, bits.count_below(0));
+    assert_eq!(1, bits.count_below(15));
+    assert_eq!(2, bits.count_below(16));
+    assert_eq!(4, bits.count_below(60));
+    assert_eq!(4, bits.count_below(u8::MAX));
+}
+
+#[test]
+fn is_subset_of_checks_every_value() {
+    let quarters: Bits<u64> = [0, 15, 30, 45].into_iter().collect();
+    let halves: Bits<u64> = [0, 30].into_iter().collect();
+    assert!(halves.is_subset_of(quarters));
+    assert!(!quarters.is_subset_of(halves));
+    assert!(Bits::default().is_subset_of(halves));
+}
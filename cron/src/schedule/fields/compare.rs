@@ -0,0 +1,57 @@
+//! Compares the datetimes of two `FieldTable`s without going
+//! through them. A table runs at every time of day it has on
+//! every date it has, so one table's datetimes are all in
+//! another's when its times of day are, and its dates are.
+
+use super::{bits::Bits, FieldTable, FIRST_YEAR, YEARS_IN_A_CYCLE};
+
+impl FieldTable {
+    /// Returns true if every local datetime that fits this
+    /// table also fits `other`.
+    pub fn is_subset_of(&self, other: &FieldTable) -> bool {
+        // Building a table makes sure that every field has something
+        // in it, so none of its times of day can be left out.
+        self.secs.bits().is_subset_of(other.secs.bits())
+            && self.mins.bits().is_subset_of(other.mins.bits())
+            && self.hours.bits().is_subset_of(other.hours.bits())
+            && self.dates_are_subset_of(other)
+    }
+
+    /// Returns true if every date that this table runs
+    /// on is also one that `other` runs on.
+    ///
+    /// Days like `L` and `5#3` land on different dates from one
+    /// month to the next, so the days get compared a month at a
+    /// time. The calendar repeats itself every `YEARS_IN_A_CYCLE`
+    /// years, and years can only be restricted to ones inside of
+    /// the first cycle, so going through that cycle covers them all.
+    fn dates_are_subset_of(&self, other: &FieldTable) -> bool {
+        // A table that runs in any year keeps going long after the
+        // last year that `other` could be restricted to, since a
+        // table that was built always runs at some point.
+        if self.years.values().is_none() && other.years.values().is_some() {
+            return false;
+        }
+
+        let mut year = FIRST_YEAR;
+        while let Some(next) = self.years.first_from(year) {
+            if next >= FIRST_YEAR + YEARS_IN_A_CYCLE {
+                break;
+            }
+            let covered = (1..=12)
+                .filter(|&month| self.months.contains(month))
+                .all(|month| {
+                    let theirs = match other.years.contains(next) && other.months.contains(month) {
+                        true => other.days.in_month(month, next),
+                        false => Bits::default(),
+                    };
+                    self.days.in_month(month, next).is_subset_of(theirs)
+                });
+            if !covered {
+                return false;
+            }
+            year = next + 1;
+        }
+        true
+    }
+}
@@ -0,0 +1,171 @@
+//! Counts the datetimes in a `FieldTable` without going through
+//! them one by one. Every day that the table allows has the same
+//! times of day, so a day holds the hours times the minutes times
+//! the seconds, and a month holds that many for each of its days.
+
+use super::{bits::Bits, year_bounds, year_of, FieldTable, LAST_YEAR};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+impl FieldTable {
+    /// Returns how many local datetimes fit the table, out of
+    /// the ones that are equal to or later than `from`, and
+    /// earlier than `until`. Only the years that a
+    /// schedule can run in get counted.
+    pub fn count(&self, from: &NaiveDateTime, until: &NaiveDateTime) -> u64 {
+        let Some((first, last)) = year_bounds() else {
+            return 0;
+        };
+        let from = (*from).max(first);
+        let until = (*until).min(last + Duration::seconds(1));
+        if from >= until {
+            return 0;
+        }
+        let (first, last) = (from.date(), until.date());
+        if first == last {
+            return match self.has_date(&first) {
+                true => self.times_before(&until.time()) - self.times_before(&from.time()),
+                false => 0,
+            };
+        }
+
+        let mut count = 0;
+        if self.has_date(&first) {
+            count += self.times_a_day() - self.times_before(&from.time());
+        }
+        if let Some(next) = first.succ_opt() {
+            count += self.days_between(next, last) * self.times_a_day();
+        }
+        if self.has_date(&last) {
+            count += self.times_before(&until.time());
+        }
+        count
+    }
+
+    /// Returns the `n`th local datetime that fits the table,
+    /// counting from 1, out of the ones that are equal to or
+    /// later than `from`.
+    ///
+    /// Returns `None` for an `n` of 0, or if the schedule
+    /// stops before getting that far.
+    pub fn nth_from(&self, from: &NaiveDateTime, n: u64) -> Option<NaiveDateTime> {
+        let mut index = n.checked_sub(1)?;
+        let (first, _) = year_bounds()?;
+        let from = (*from).max(first);
+        let per_day = self.times_a_day();
+        let date = from.date();
+        if self.has_date(&date) {
+            let skipped = self.times_before(&from.time());
+            if skipped + index < per_day {
+                return Some(date.and_time(self.time_at(skipped + index)?));
+            }
+            index -= per_day - skipped;
+        }
+
+        let next = date.succ_opt()?;
+        let mut year = year_of(&next)?;
+        let (mut month, mut day) = (next.month() as u8, next.day() as u8);
+        while year <= LAST_YEAR {
+            match self.years.first_from(year)? {
+                next if next == year => {}
+                next => (year, month, day) = (next, 1, 1),
+            }
+            if self.months.contains(month) {
+                let days = self
+                    .days
+                    .in_month(month, year)
+                    .intersection(Bits::range(day, 31));
+                let count = days.len() as u64 * per_day;
+                if index < count {
+                    let day = days.iter().nth((index / per_day) as usize)?;
+                    let time = self.time_at(index % per_day)?;
+                    return NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+                        .map(|date| date.and_time(time));
+                }
+                index -= count;
+            }
+            (month, day) = (month % 12 + 1, 1);
+            if month == 1 {
+                year += 1;
+            }
+        }
+        None
+    }
+
+    /// Returns true if the table runs at some point on `date`.
+    fn has_date(&self, date: &NaiveDate) -> bool {
+        let Some(year) = year_of(date) else {
+            return false;
+        };
+        let month = date.month() as u8;
+        self.years.contains(year)
+            && self.months.contains(month)
+            && self.days.contains(date.day() as u8, month, year)
+    }
+
+    /// Returns how many dates from `from` up to, but not
+    /// including, `until` the table runs on, a month at a time.
+    fn days_between(&self, from: NaiveDate, until: NaiveDate) -> u64 {
+        let (Ok(mut year), Ok(end_year)) =
+            (u32::try_from(from.year()), u32::try_from(until.year()))
+        else {
+            return 0;
+        };
+        let (mut month, mut day) = (from.month() as u8, from.day() as u8);
+        let end = (end_year, until.month() as u8);
+        let mut count = 0;
+        while (year, month) <= end {
+            // Going back a day from the 1st lands on the
+            // 0th, which leaves nothing in the range.
+            let last = match (year, month) == end {
+                true => until.day() as u8 - 1,
+                false => 31,
+            };
+            if self.years.contains(year) && self.months.contains(month) {
+                let days = self.days.in_month(month, year);
+                count += days.intersection(Bits::range(day, last)).len() as u64;
+            }
+            (month, day) = (month % 12 + 1, 1);
+            if month == 1 {
+                year += 1;
+            }
+        }
+        count
+    }
+
+    /// Returns how many times of day the table runs at on
+    /// each of the days that it runs on.
+    fn times_a_day(&self) -> u64 {
+        let (hours, mins, secs) = (self.hours.bits(), self.mins.bits(), self.secs.bits());
+        hours.len() as u64 * mins.len() as u64 * secs.len() as u64
+    }
+
+    /// Returns how many of the table's times of
+    /// day come strictly before `time`.
+    fn times_before(&self, time: &NaiveTime) -> u64 {
+        let (hours, mins, secs) = (self.hours.bits(), self.mins.bits(), self.secs.bits());
+        let (hour, min) = (time.hour() as u8, time.minute() as u8);
+        // Partway through a second is already past the start of it.
+        let sec = time.second() as u8 + u8::from(time.nanosecond() > 0);
+
+        let mut count = hours.count_below(hour) as u64 * mins.len() as u64 * secs.len() as u64;
+        if hours.contains(hour) {
+            count += mins.count_below(min) as u64 * secs.len() as u64;
+            if mins.contains(min) {
+                count += secs.count_below(sec) as u64;
+            }
+        }
+        count
+    }
+
+    /// Returns the table's time of day at `index`, counting
+    /// from 0 at the earliest one, which is found by splitting
+    /// the index up between the hours, minutes and seconds.
+    fn time_at(&self, index: u64) -> Option<NaiveTime> {
+        let (hours, mins, secs) = (self.hours.bits(), self.mins.bits(), self.secs.bits());
+        let (per_hour, per_min) = (mins.len() as u64 * secs.len() as u64, secs.len() as u64);
+        let hour = hours.iter().nth((index / per_hour) as usize)?;
+        let min = mins.iter().nth((index % per_hour / per_min) as usize)?;
+        let sec = secs.iter().nth((index % per_min) as usize)?;
+        NaiveTime::from_hms_opt(hour as u32, min as u32, sec as u32)
+    }
+}
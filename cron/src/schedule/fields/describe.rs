@@ -0,0 +1,327 @@
+//! Turns a `FieldTable` into an English sentence, like
+//! "At 13:16:00 and 18:16:00, Monday through Friday".
+
+use super::{
+    inner::{MonthDays, WeekDays},
+    FieldTable, MonthDayModifier, WeekdayModifier,
+};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+const NTH: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+/// Times of day get written out one by one
+/// up to this many, and as ranges after that.
+const MAX_TIMES: usize = 6;
+
+/// The shape of the values in a single field.
+enum Values<'a> {
+    /// Every value the field can hold, like `*`.
+    Every,
+    /// Evenly spaced values starting from the smallest one
+    /// and going all the way around, like `*/15`.
+    Step(u32),
+    /// Anything else.
+    Some(&'a [u32]),
+}
+
+impl<'a> Values<'a> {
+    fn new(values: &'a [u32], min: u32, max: u32) -> Self {
+        if values.len() as u32 == max - min + 1 {
+            return Values::Every;
+        }
+        match *values {
+            [first, second, third, ..] if first == min && third - second == second - first => {
+                let step = second - first;
+                let evenly_spaced = values.windows(2).all(|pair| pair[1] - pair[0] == step);
+                if evenly_spaced && values[values.len() - 1] + step > max {
+                    Values::Step(step)
+                } else {
+                    Values::Some(values)
+                }
+            }
+            _ => Values::Some(values),
+        }
+    }
+}
+
+impl FieldTable {
+    /// Describes the table in English, built from the values in
+    /// each field rather than from how they were written, so
+    /// `0 16 13-18 * * 1-5` and `0 16 13,14,15,16,17,18 * * Mon-Fri`
+    /// are described the same way.
+    pub fn describe(&self) -> String {
+        let widen = |values: &[u8]| values.iter().map(|&value| value as u32).collect::<Vec<_>>();
+        let (secs, mins, hours) = (
+            widen(&self.secs.values()),
+            widen(&self.mins.values()),
+            widen(&self.hours.values()),
+        );
+
+        let mut parts = vec![times(&secs, &mins, &hours)];
+        parts.extend(days(self.days.parts()));
+        let months = widen(&self.months.values());
+        match Values::new(&months, 1, 12) {
+            Values::Every => {}
+            Values::Step(step) => parts.push(format!("in every {} month", ordinal(step))),
+            Values::Some(months) => parts.push(format!(
+                "in {}",
+                spans(months, |month| MONTHS[month as usize - 1].to_owned())
+            )),
+        }
+        if let Some(years) = self.years.values() {
+            parts.push(format!("in {}", spans(years, |year| year.to_string())));
+        }
+
+        let description = parts.join(", ");
+        let mut chars = description.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => description,
+        }
+    }
+}
+
+/// Describes the time of day. When both the seconds and the
+/// minutes are a single value, the times are written like a
+/// clock, and otherwise each field gets described on its own.
+fn times(secs: &[u32], mins: &[u32], hours: &[u32]) -> String {
+    let clock = |hour: u32| format!("{hour:02}:{:02}:{:02}", mins[0], secs[0]);
+    match (secs, mins) {
+        ([sec], [min]) => match Values::new(hours, 0, 23) {
+            Values::Every if (*sec, *min) == (0, 0) => "every hour".to_owned(),
+            Values::Every => {
+                let amounts = [(*min, "minute"), (*sec, "second")]
+                    .into_iter()
+                    .filter(|(amount, _)| *amount > 0)
+                    .map(|(amount, name)| match amount {
+                        1 => format!("1 {name}"),
+                        _ => format!("{amount} {name}s"),
+                    });
+                format!("at {} past every hour", list(amounts))
+            }
+            Values::Step(step) if (*sec, *min) == (0, 0) => format!("every {step} hours"),
+            _ if hours.len() <= MAX_TIMES => {
+                format!("at {}", list(hours.iter().map(|&hour| clock(hour))))
+            }
+            Values::Step(step) => format!(
+                "every {step} hours from {} through {}",
+                clock(hours[0]),
+                clock(hours[hours.len() - 1])
+            ),
+            _ => {
+                let runs = hours.chunk_by(|a, b| a + 1 == *b).map(|run| match *run {
+                    [first, _, .., last] => {
+                        format!("every hour from {} through {}", clock(first), clock(last))
+                    }
+                    _ => list(run.iter().map(|&hour| format!("at {}", clock(hour)))),
+                });
+                list(runs)
+            }
+        },
+        _ => fields(secs, mins, hours),
+    }
+}
+
+/// Describes the seconds, minutes and hours one after the
+/// other, leaving out whatever the others already imply.
+fn fields(secs: &[u32], mins: &[u32], hours: &[u32]) -> String {
+    let mut parts = Vec::new();
+    let joiner = |parts: &Vec<String>| if parts.is_empty() { "at" } else { "during" };
+
+    let secs = Values::new(secs, 0, 59);
+    let every_second = match secs {
+        Values::Every => {
+            parts.push("every second".to_owned());
+            true
+        }
+        Values::Step(step) => {
+            parts.push(format!("every {step} seconds"));
+            true
+        }
+        // On the minute, which goes without saying.
+        Values::Some([0]) => false,
+        Values::Some(secs) => {
+            parts.push(format!("at {}", unit(secs, "second")));
+            false
+        }
+    };
+
+    let mins = Values::new(mins, 0, 59);
+    let mins_listed = match mins {
+        Values::Every if every_second => false,
+        Values::Every if parts.is_empty() => {
+            parts.push("every minute".to_owned());
+            false
+        }
+        Values::Every => {
+            push_to_last(&mut parts, "of every minute");
+            false
+        }
+        Values::Step(step) => {
+            parts.push(format!("every {step} minutes"));
+            false
+        }
+        Values::Some(mins) => {
+            parts.push(format!("{} {}", joiner(&parts), unit(mins, "minute")));
+            true
+        }
+    };
+
+    match Values::new(hours, 0, 23) {
+        Values::Every if mins_listed => push_to_last(&mut parts, "of every hour"),
+        Values::Every => {}
+        Values::Step(step) => parts.push(format!("during every {} hour", ordinal(step))),
+        Values::Some(hours) => parts.push(format!("{} {}", joiner(&parts), unit(hours, "hour"))),
+    }
+    parts.join(", ")
+}
+
+/// Finishes off the last part, like "at second 30" becoming
+/// "at second 30 of every minute".
+fn push_to_last(parts: &mut [String], words: &str) {
+    if let Some(last) = parts.last_mut() {
+        last.push(' ');
+        last.push_str(words);
+    }
+}
+
+/// Describes the days of the month and of the week. When
+/// both are given, a day only needs to match one of them.
+fn days(parts: (Option<&MonthDays>, Option<&WeekDays>)) -> Option<String> {
+    match parts {
+        // Either part running every day makes the other one moot.
+        (Some(month), Some(week)) => {
+            let (month, week) = (month_days(month)?, week_days(week, false)?);
+            Some(format!("{month} or {week}"))
+        }
+        (Some(month), None) => month_days(month),
+        (None, Some(week)) => week_days(week, true),
+        (None, None) => None,
+    }
+}
+
+fn month_days(month: &MonthDays) -> Option<String> {
+    let days: Vec<u32> = month.days().into_iter().map(u32::from).collect();
+    if month.modifiers().is_empty() && matches!(Values::new(&days, 1, 31), Values::Every) {
+        return None;
+    }
+
+    let mut modifiers = month.modifiers().to_vec();
+    modifiers.sort();
+    modifiers.dedup();
+    let days = (!days.is_empty()).then(|| format!("the {}", spans(&days, ordinal)));
+    let modifiers = modifiers.into_iter().map(|modifier| match modifier {
+        MonthDayModifier::Last { offset: 0 } => "the last day".to_owned(),
+        MonthDayModifier::Last { offset } => {
+            format!("the {} day before the last day", ordinal(offset as u32))
+        }
+        MonthDayModifier::NearestWeekday(day) => {
+            format!("the weekday nearest the {}", ordinal(day as u32))
+        }
+        MonthDayModifier::LastWeekday => "the last weekday".to_owned(),
+    });
+    Some(format!(
+        "on {} of the month",
+        list(days.into_iter().chain(modifiers))
+    ))
+}
+
+/// Describes the days of the week. Plain weekdays on their own
+/// read like "Monday through Friday" or "only on Monday", and
+/// otherwise they're said to be "on" the days.
+fn week_days(week: &WeekDays, alone: bool) -> Option<String> {
+    let days: Vec<u32> = week.days().into_iter().map(u32::from).collect();
+    if week.modifiers().is_empty() && matches!(Values::new(&days, 0, 6), Values::Every) {
+        return None;
+    }
+
+    let weekday = |day: u32| WEEKDAYS[day as usize].to_owned();
+    let mut modifiers = week.modifiers().to_vec();
+    modifiers.sort();
+    modifiers.dedup();
+    if modifiers.is_empty() && alone {
+        let days = spans(&days, weekday);
+        return Some(match days.contains(" through ") {
+            true => days,
+            false => format!("only on {days}"),
+        });
+    }
+
+    let days = (!days.is_empty()).then(|| spans(&days, weekday));
+    let modifiers = modifiers.into_iter().map(|modifier| match modifier {
+        WeekdayModifier::Last(day) => format!("the last {} of the month", weekday(day as u32)),
+        WeekdayModifier::Nth { weekday: day, nth } => format!(
+            "the {} {} of the month",
+            NTH[nth as usize - 1],
+            weekday(day as u32)
+        ),
+    });
+    Some(format!("on {}", list(days.into_iter().chain(modifiers))))
+}
+
+/// Writes a unit with its values, like "second 30" or
+/// "minutes 0 through 10 and 30".
+fn unit(values: &[u32], name: &str) -> String {
+    let plural = if values.len() == 1 { "" } else { "s" };
+    format!(
+        "{name}{plural} {}",
+        spans(values, |value| value.to_string())
+    )
+}
+
+/// Lists the sorted `values`, with runs of three or more
+/// written as ranges, like "Monday through Friday".
+fn spans(values: &[u32], name: impl Fn(u32) -> String) -> String {
+    let runs = values
+        .chunk_by(|a, b| a + 1 == *b)
+        .flat_map(|run| match *run {
+            [first, _, .., last] => vec![format!("{} through {}", name(first), name(last))],
+            _ => run.iter().map(|&value| name(value)).collect(),
+        });
+    list(runs)
+}
+
+/// Joins the items with commas, and "and" before the last one.
+fn list(items: impl IntoIterator<Item = String>) -> String {
+    let mut items: Vec<String> = items.into_iter().collect();
+    match items.pop() {
+        Some(last) if items.is_empty() => last,
+        Some(last) => format!("{} and {last}", items.join(", ")),
+        None => String::new(),
+    }
+}
+
+fn ordinal(day: u32) -> String {
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{day}{suffix}")
+}
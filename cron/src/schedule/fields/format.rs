@@ -0,0 +1,116 @@
+use crate::schedule::parser::{MONTHS, WEEKDAYS};
+use std::fmt::{self, Write};
+
+/// How to write out the values of a single field.
+pub(super) struct Format {
+    pub min: u32,
+    pub max: u32,
+    /// Whether every value can be written as `*`. The day
+    /// fields can't always use it, since a `*` there changes
+    /// which of the two day fields gets used. A step like `*/2`
+    /// doesn't, so that can be written either way.
+    pub wildcard: bool,
+    pub name: fn(u32) -> String,
+}
+
+impl Format {
+    pub fn number(min: u32, max: u32) -> Self {
+        Self {
+            min,
+            max,
+            wildcard: true,
+            name: |value| value.to_string(),
+        }
+    }
+
+    pub fn months() -> Self {
+        Self {
+            name: month_name,
+            ..Format::number(1, 12)
+        }
+    }
+
+    pub fn weekdays() -> Self {
+        Self {
+            name: weekday_name,
+            ..Format::number(0, 6)
+        }
+    }
+
+    pub fn without_wildcard(self) -> Self {
+        Self {
+            wildcard: false,
+            ..self
+        }
+    }
+
+    /// Writes out the sorted `values` as short as they can be
+    /// while parsing back to the same thing. That's a `*` for
+    /// every value, a step like `*/15` or `1-10/3` for evenly
+    /// spaced values, and otherwise a list where runs of three
+    /// or more values get collapsed into ranges.
+    pub fn write(&self, out: &mut impl Write, values: &[u32]) -> fmt::Result {
+        let name = self.name;
+        let (first, last) = match values {
+            [] => return Ok(()),
+            [first, .., last] => (*first, *last),
+            [only] => return out.write_str(&name(*only)),
+        };
+        let full = values.len() as u32 == self.max - self.min + 1;
+
+        if full && self.wildcard {
+            return out.write_str("*");
+        }
+        if let Some(step) = step(values).filter(|step| *step > 1 && values.len() >= 3) {
+            return if first == self.min && last + step > self.max {
+                write!(out, "*/{step}")
+            } else {
+                write!(out, "{}-{}/{step}", name(first), name(last))
+            };
+        }
+
+        let mut runs = values.chunk_by(|a, b| a + 1 == *b).peekable();
+        while let Some(run) = runs.next() {
+            match *run {
+                [first, _, .., last] => write!(out, "{}-{}", name(first), name(last))?,
+                _ => write_list(out, run.iter().map(|value| name(*value)))?,
+            }
+            if runs.peek().is_some() {
+                out.write_char(',')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the distance between each value,
+/// if they're all the same distance apart.
+fn step(values: &[u32]) -> Option<u32> {
+    let step = values.get(1)? - values[0];
+    values
+        .windows(2)
+        .all(|pair| pair[1] - pair[0] == step)
+        .then_some(step)
+}
+
+/// Writes out each item, separated by commas.
+pub(super) fn write_list(
+    out: &mut impl Write,
+    items: impl IntoIterator<Item = impl fmt::Display>,
+) -> fmt::Result {
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            out.write_char(',')?;
+        }
+        write!(out, "{item}")?;
+    }
+    Ok(())
+}
+
+pub(super) fn month_name(month: u32) -> String {
+    MONTHS[month as usize - 1].to_owned()
+}
+
+pub(super) fn weekday_name(weekday: u32) -> String {
+    WEEKDAYS[weekday as usize].to_owned()
+}
@@ -1,568 +1,661 @@
-use std::cmp::min;
-
-use crate::schedule::iterator::CopyRing;
+use super::{
+    bits::{Bits, Word},
+    format::{self, Format},
+    Error,
+};
+use crate::Field;
+use std::fmt::{self, Write};
 
 #[derive(Clone, Debug)]
-pub(super) struct Seconds(CopyRing<u8>);
+pub(super) struct Seconds(Bits<u64>);
 
 #[derive(Clone, Debug)]
-pub(super) struct Minutes(CopyRing<u8>);
+pub(super) struct Minutes(Bits<u64>);
 
 #[derive(Clone, Debug)]
-pub(super) struct Hours(CopyRing<u8>);
+pub(super) struct Hours(Bits<u32>);
 
 #[derive(Clone, Debug)]
 pub(super) enum Days {
-    Both {
-        month: CopyRing<u8>,
-        week: (CopyRing<u8>, WeekSummary),
-    },
-    Month(CopyRing<u8>),
-    Week((CopyRing<u8>, WeekSummary)),
+    Both { month: MonthDays, week: WeekDays },
+    Month(MonthDays),
+    Week(WeekDays),
 }
 
-#[derive(Clone, Debug, Default)]
-pub(super) struct WeekSummary {
-    last_month_day: u8,
-    last_weekday: u8,
-    last_used: LastUsed,
+/// The days of the month field, made up of plain
+/// days like `1,15` along with any modifiers.
+#[derive(Clone, Debug)]
+pub(super) struct MonthDays {
+    days: Bits<u32>,
+    modifiers: Vec<MonthDayModifier>,
+}
+
+/// The days of the week field, made up of plain
+/// weekdays like `Mon-Fri` along with any modifiers.
+#[derive(Clone, Debug)]
+pub(super) struct WeekDays {
+    days: Bits<u8>,
+    modifiers: Vec<WeekdayModifier>,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-pub(super) enum LastUsed {
-    Week,
-    #[default]
-    Month,
-    Both,
+/// Days of the month that land on a different date
+/// depending on the month, written with `L` and `W`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MonthDayModifier {
+    /// `L`, the last day of the month, or `L-3`,
+    /// which is three days before the last day.
+    Last { offset: u8 },
+    /// `15W`, the weekday (Monday through Friday) that is
+    /// closest to the 15th without leaving the month.
+    NearestWeekday(u8),
+    /// `LW`, the last weekday of the month.
+    LastWeekday,
 }
 
-impl WeekSummary {
-    pub fn set(&mut self, new_summary: WeekSummary) {
-        self.last_month_day = new_summary.last_month_day;
-        self.last_used = new_summary.last_used;
-        self.last_weekday = new_summary.last_weekday;
+/// Days of the week that only count once a month,
+/// written with `L` and `#`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WeekdayModifier {
+    /// `5L`, the last Friday of the month.
+    Last(u8),
+    /// `2#1`, the first Tuesday of the month.
+    Nth { weekday: u8, nth: u8 },
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Months(Bits<u16>);
+
+/// The years that a schedule is allowed to run in, sorted.
+/// An empty `Years` places no restriction
+/// on the year at all.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Years(Option<Vec<u32>>);
+
+impl Seconds {
+    pub fn new(bits: Bits<u64>) -> Self {
+        Self(bits)
     }
 
-    pub(super) fn last_month_day(&self) -> u8 {
-        self.last_month_day
+    pub fn bits(&self) -> Bits<u64> {
+        self.0
     }
 
-    pub(super) fn last_weekday(&self) -> u8 {
-        self.last_weekday
+    /// Returns the earliest second that is equal to
+    /// or later than `secs`, or `None` if the minute
+    /// runs out before one is found.
+    pub fn first_from(&self, secs: u8) -> Option<u8> {
+        self.0.first_from(secs)
     }
 
-    pub(super) fn last_used(&self) -> LastUsed {
-        self.last_used
+    /// Returns the latest second that is equal to
+    /// or earlier than `secs`, or `None` if the minute
+    /// runs out before one is found.
+    pub fn last_until(&self, secs: u8) -> Option<u8> {
+        self.0.last_until(secs)
     }
 
-    pub(super) fn set_last_used(&mut self, last_used: LastUsed) {
-        self.last_used = last_used;
+    pub fn contains(&self, secs: u8) -> bool {
+        self.0.contains(secs)
     }
-}
 
-#[derive(Clone, Debug)]
-pub(super) struct Months(CopyRing<u8>);
+    pub fn values(&self) -> Vec<u8> {
+        self.0.iter().collect()
+    }
+}
 
-impl Seconds {
-    pub fn new(copy_ring: CopyRing<u8>) -> Self {
-        Self(copy_ring)
+impl Minutes {
+    pub fn new(bits: Bits<u64>) -> Self {
+        Self(bits)
     }
 
-    /// Returns the first second that occurs after the given
-    /// number of seconds. Rotates the inner buffer so that
-    /// calling `next` yields the following value.
-    ///
-    /// If the inner buffer wrapped back to the earliest second,
-    /// then overflow has occurred and the bool is `true`.
-    ///
-    /// Otherwise, the bool is `false` and no overflow
-    /// has occurred.
-    pub fn first_after(&mut self, secs: u8) -> (u8, bool) {
-        let mut found = false;
-        self.0.reset();
-        for seconds in self.0.until_start() {
-            if seconds >= secs {
-                found = true;
-                break;
-            }
-        }
-        if found {
-            self.0.rotate_right(1);
-            (self.0.next().unwrap(), false)
-        } else {
-            (self.0.next().unwrap(), true)
-        }
+    pub fn bits(&self) -> Bits<u64> {
+        self.0
     }
 
-    /// Returns the next second in the inner
-    /// buffer, along with whether overflow
-    /// occurred. For seconds, overflow
-    /// occurs when the seconds passes 59
-    /// and wraps back to 0.
-    pub fn next(&mut self) -> (u8, bool) {
-        self.0.checked_next().unwrap()
+    /// Returns the earliest minute that is equal to
+    /// or later than `mins`, or `None` if the hour
+    /// runs out before one is found.
+    pub fn first_from(&self, mins: u8) -> Option<u8> {
+        self.0.first_from(mins)
     }
-}
 
-impl Minutes {
-    pub fn new(copy_ring: CopyRing<u8>) -> Self {
-        Self(copy_ring)
+    /// Returns the latest minute that is equal to
+    /// or earlier than `mins`, or `None` if the hour
+    /// runs out before one is found.
+    pub fn last_until(&self, mins: u8) -> Option<u8> {
+        self.0.last_until(mins)
     }
 
-    /// Returns the first minute that occurs after the given
-    /// number of minutes. Rotates the inner buffer so that
-    /// calling `next` yields the following value.
-    ///
-    /// If the inner buffer wrapped back to the earliest minute,
-    /// then overflow has occurred and the bool is `true`.
-    ///
-    /// Otherwise, the bool is `false` and no overflow
-    /// has occurred.
-    pub fn first_after(&mut self, mins: u8, secs_overflow: bool) -> (u8, bool) {
-        let mut found = false;
-        let mut overflow = false;
-        self.0.reset();
-        for minutes in self.0.one_cycle().checked() {
-            if minutes.0 >= mins {
-                found = true;
-                overflow = minutes.1;
-                break;
-            }
-            eprintln!("minute: {:?} --- mins: {}", minutes, mins);
-        }
-        if secs_overflow {
-            overflow = self.0.checked_next().unwrap().1 || overflow;
-            dbg!(overflow);
-        }
-        if found {
-            self.0.rotate_right(1);
-            let final_overflow = secs_overflow && overflow;
-            (self.0.peek().unwrap(), final_overflow)
-        } else {
-            (self.0.peek().unwrap(), true)
-        }
+    pub fn contains(&self, mins: u8) -> bool {
+        self.0.contains(mins)
     }
 
-    /// Returns the next minute in the inner
-    /// buffer, along with whether overflow
-    /// occurred. For minutes, overflow
-    /// occurs when the minutes passes 59
-    /// and wraps back to 0.
-    pub fn next(&mut self, secs_overflow: bool) -> (u8, bool) {
-        if secs_overflow {
-            self.0.checked_next().unwrap()
-        } else {
-            (self.0.peek().unwrap(), false)
-        }
+    pub fn values(&self) -> Vec<u8> {
+        self.0.iter().collect()
     }
 }
 
 impl Hours {
-    pub fn new(copy_ring: CopyRing<u8>) -> Self {
-        Self(copy_ring)
+    pub fn new(bits: Bits<u32>) -> Self {
+        Self(bits)
     }
 
-    /// Returns the first hour that occurs after the given
-    /// number of hours. Rotates the inner buffer so that
-    /// calling `next` yields the following value.
-    ///
-    /// If the inner buffer wrapped back to the earliest hour,
-    /// then overflow has occurred and the bool is `true`.
+    pub fn bits(&self) -> Bits<u32> {
+        self.0
+    }
+
+    /// Returns the earliest hour that is equal to
+    /// or later than `hrs`, or `None` if the day
+    /// runs out before one is found.
+    pub fn first_from(&self, hrs: u8) -> Option<u8> {
+        self.0.first_from(hrs)
+    }
+
+    /// Returns the latest hour that is equal to
+    /// or earlier than `hrs`, or `None` if the day
+    /// runs out before one is found.
+    pub fn last_until(&self, hrs: u8) -> Option<u8> {
+        self.0.last_until(hrs)
+    }
+
+    pub fn contains(&self, hrs: u8) -> bool {
+        self.0.contains(hrs)
+    }
+
+    pub fn values(&self) -> Vec<u8> {
+        self.0.iter().collect()
+    }
+}
+
+impl Days {
+    /// Returns the earliest day of the month that is equal to
+    /// or later than `day`, or `None` if the month runs out
+    /// before one is found.
     ///
-    /// Otherwise, the bool is `false` and no overflow
-    /// has occurred.
-    pub fn first_after(&mut self, hrs: u8, mins_overflow: bool) -> (u8, bool) {
-        let mut found = false;
-        let mut overflow = false;
-        self.0.reset();
-        for hour in self.0.one_cycle().checked() {
-            if hour.0 >= hrs {
-                found = true;
-                overflow = hour.1;
-                break;
+    /// Unlike the other fields, the answer depends on which
+    /// month and year we're in, since both the length of the
+    /// month and the weekday of each date change between them.
+    pub fn first_from(&self, day: u8, month: u8, year: u32) -> Option<u8> {
+        self.in_month(month, year).first_from(day)
+    }
+
+    /// Returns the latest day of the month that is equal to
+    /// or earlier than `day`, or `None` if the month runs out
+    /// before one is found. A `day` past the end of the month
+    /// starts from the last day of the month instead.
+    pub fn last_until(&self, day: u8, month: u8, year: u32) -> Option<u8> {
+        self.in_month(month, year).last_until(day)
+    }
+
+    /// Returns true if the given date is one of the days
+    /// in this field. When both the days of the month and the days
+    /// of the week were given, matching either one is enough.
+    pub fn contains(&self, day: u8, month: u8, year: u32) -> bool {
+        self.in_month(month, year).contains(day)
+    }
+
+    /// Returns every day of the given month that is in this
+    /// field, so that searching through the month is a bit scan.
+    pub fn in_month(&self, month: u8, year: u32) -> Bits<u32> {
+        // Plain days of the month don't care which weekday the
+        // month starts on, which is the slow part to work out.
+        if let Days::Month(days) = self {
+            if days.modifiers.is_empty() {
+                return days
+                    .days
+                    .intersection(Bits::range(1, crate::days_in_a_month(month, year)));
             }
         }
-        if mins_overflow {
-            overflow = self.0.checked_next().unwrap().1 || overflow
-        }
-        if found {
-            self.0.rotate_right(1);
-            let final_overflow = mins_overflow && overflow;
-            (self.0.peek().unwrap(), final_overflow)
-        } else {
-            (self.0.peek().unwrap(), true)
+        let shape = MonthShape::new(month, year);
+        match self {
+            Days::Both { month, week } => month.in_month(shape).union(week.in_month(shape)),
+            Days::Month(month) => month.in_month(shape),
+            Days::Week(week) => week.in_month(shape),
         }
     }
 
-    /// Returns the next hour in the inner
-    /// buffer, along with whether overflow
-    /// occurred. For hours, overflow
-    /// occurs when the hours passes 23
-    /// and wraps back to 0.
-    pub fn next(&mut self, mins_overflow: bool) -> (u8, bool) {
-        if mins_overflow {
-            self.0.checked_next().unwrap()
-        } else {
-            (self.0.peek().unwrap(), false)
+    /// Returns the two parts of the field, either
+    /// of which can be missing.
+    pub fn parts(&self) -> (Option<&MonthDays>, Option<&WeekDays>) {
+        match self {
+            Days::Both { month, week } => (Some(month), Some(week)),
+            Days::Month(month) => (Some(month), None),
+            Days::Week(week) => (None, Some(week)),
         }
     }
-}
 
-impl Days {
-    pub fn first_after(
-        &mut self,
-        day_of_month: u8,
-        day_of_week: u8, /* Sunday = 0 -----> Saturday = 6 */
-        hours_overflow: bool,
-        month: u8,
-        year: u32,
-    ) -> (u8, bool) {
-        use std::cmp::Ordering;
-        let days_in_curr_month = crate::days_in_a_month(month, year);
+    /// Writes out the days of the month and the days of the
+    /// week as two separate fields, picking between `*` and
+    /// an explicit list so that they parse back to the same
+    /// parts of the field.
+    pub fn to_fields(&self) -> Result<(String, String), fmt::Error> {
+        let (mut month_field, mut week_field) = (String::new(), String::new());
         match self {
             Days::Both { month, week } => {
-                let (week, summary) = week;
-                let next_day_week = Self::next_day_of_the_week(
-                    week,
-                    hours_overflow,
-                    day_of_week,
-                    day_of_month,
-                    days_in_curr_month,
-                );
-                let next_day_month = Self::next_day_of_the_month(
-                    month,
-                    hours_overflow,
-                    day_of_month,
-                    days_in_curr_month,
-                );
-
-                match (next_day_week.1, next_day_month.1) {
-                    (true, true) | (false, false) => match next_day_month.0.cmp(&next_day_week.0) {
-                        Ordering::Less => {
-                            summary.last_used = LastUsed::Month;
-                            summary.last_month_day = next_day_month.0;
-                            summary.last_weekday = next_weekday_from_last(
-                                day_of_week,
-                                next_day_month.0,
-                                days_in_curr_month,
-                                day_of_month,
-                            );
-                            next_day_month
-                        }
-                        Ordering::Equal => {
-                            summary.last_used = LastUsed::Both;
-                            summary.last_month_day = next_day_month.0;
-                            summary.last_weekday = week.peek().unwrap();
-                            next_day_month
-                        } // Doesn't matter which one to return
-                        Ordering::Greater => {
-                            summary.last_used = LastUsed::Week;
-                            summary.last_month_day = next_day_week.0;
-                            summary.last_weekday = week.peek().unwrap();
-                            next_day_week
-                        }
-                    },
-                    (true, false) => {
-                        // The month day was sooner, commit to the month day
-                        summary.last_used = LastUsed::Month;
-                        summary.last_month_day = next_day_month.0;
-                        summary.last_weekday = next_weekday_from_last(
-                            day_of_week,
-                            next_day_month.0,
-                            days_in_curr_month,
-                            day_of_month,
-                        );
-                        next_day_month
-                    }
-                    (false, true) => {
-                        // The weekday was sooner, commit to the weekday
-                        summary.last_used = LastUsed::Week;
-                        summary.last_month_day = next_day_week.0;
-                        summary.last_weekday = week.peek().unwrap();
-                        next_day_week
-                    }
-                }
+                month.write(&mut month_field, Format::number(1, 31).without_wildcard())?;
+                week.write(&mut week_field, Format::weekdays().without_wildcard())?;
             }
             Days::Month(month) => {
-                Self::next_day_of_the_month(month, hours_overflow, day_of_month, days_in_curr_month)
+                month.write(&mut month_field, Format::number(1, 31))?;
+                week_field.push('*');
             }
-            Days::Week((week, summary)) => {
-                let next = Self::next_day_of_the_week(
-                    week,
-                    hours_overflow,
-                    day_of_week,
-                    day_of_month,
-                    days_in_curr_month,
-                );
-                summary.last_used = LastUsed::Week;
-                summary.last_month_day = next.0;
-                summary.last_weekday = week.peek().unwrap();
-                next
+            Days::Week(week) => {
+                month_field.push('*');
+                week.write(&mut week_field, Format::weekdays().without_wildcard())?;
             }
         }
+        Ok((month_field, week_field))
     }
 
-    pub fn next(&mut self, hours_overflow: bool, curr_month: u8, curr_year: u32) -> (u8, bool) {
-        use std::cmp::Ordering;
-        let weekday_to_month_day =
-            |weekday: u8, last_weekday: u8, days_in_curr_month: u8| -> (u8, bool) {
-                let days_since_last = Self::num_weekdays_since(last_weekday as i8, weekday as i8);
-                let next_day_of_the_month = last_weekday + days_since_last;
-                let overflow = next_day_of_the_month > days_in_curr_month;
-                let month_day = if overflow {
-                    next_day_of_the_month - days_in_curr_month
-                } else {
-                    next_day_of_the_month
-                };
-                (month_day, overflow)
-            };
-        let days_in_curr_month = crate::days_in_a_month(curr_month, curr_year);
-        match self {
-            Days::Both { month, week } => {
-                let (week, summary) = week;
-
-                // We're checking a couple different things:
-                // - Was there overflow from the hours
-                // - Which field has the soonest day
-                //
-                // Now, if there was no overflow from the hours,
-                // then we're not advancing any field, so there'd
-                // be no change to which field we used last.
-                // I don't even think we'd have to update the
-                // summary, since the days wouldn't change
-                //
-                // If there was overflow from the hours, then we really
-                // gotta do some calculations. We look at which field
-                // we used last and advance that field by one, checking
-                // for overflow there. For the other field, we just peek
-                // the next value, and assume no overflow.
-                //
-                // From there, we compare the next days like we
-                // did for the `first_after` method, and the winner
-                // goes into the summary. Then we return the value too.
-
-                hours_overflow
-                    .then(|| {
-                        let state = match summary.last_used() {
-                            LastUsed::Week => {
-                                let week = week.next().unwrap();
-                                let month = (month.peek().unwrap(), false);
-                                (month, week)
-                            }
-                            LastUsed::Month => {
-                                let month = month.checked_next().unwrap();
-                                let week = week.peek().unwrap();
-                                (month, week)
-                            }
-                            LastUsed::Both => {
-                                let month = month.checked_next().unwrap();
-                                let week = week.next().unwrap();
-                                (month, week)
-                            }
-                        };
-                        let ((monthday, monthday_overflow), weekday) = state;
-
-                        // Week
-                        let (next_day_of_the_month, weekday_overflow) = weekday_to_month_day(
-                            weekday,
-                            summary.last_weekday(),
-                            days_in_curr_month,
-                        );
-                        let week_summary = WeekSummary {
-                            last_month_day: next_day_of_the_month,
-                            last_weekday: weekday,
-                            last_used: LastUsed::Week,
-                        };
-
-                        // Month
-                        let next_day_of_the_week = next_weekday_from_last(
-                            summary.last_weekday(),
-                            monthday,
-                            days_in_curr_month,
-                            summary.last_month_day(),
-                        );
-                        let mut month_summary = WeekSummary {
-                            last_month_day: monthday,
-                            last_weekday: next_day_of_the_week,
-                            last_used: LastUsed::Month,
-                        };
-
-                        // Compare
-                        match (monthday_overflow, weekday_overflow) {
-                            (true, true) | (false, false) => {
-                                match monthday.cmp(&next_day_of_the_month) {
-                                    Ordering::Less => {
-                                        summary.set(month_summary);
-                                        (summary.last_month_day(), monthday_overflow)
-                                    }
-                                    Ordering::Equal => {
-                                        month_summary.set_last_used(LastUsed::Both);
-                                        summary.set(month_summary);
-                                        (summary.last_month_day(), monthday_overflow)
-                                    }
-                                    Ordering::Greater => {
-                                        summary.set(week_summary);
-                                        (summary.last_month_day(), weekday_overflow)
-                                    }
-                                }
-                            }
-                            (true, false) => {
-                                // The weekday was sooner
-                                summary.set(week_summary);
-                                (summary.last_month_day(), weekday_overflow)
-                            }
-                            (false, true) => {
-                                // The monthday was sooner
-                                summary.set(month_summary);
-                                (summary.last_month_day(), monthday_overflow)
-                            }
-                        }
-                    })
-                    .unwrap_or((summary.last_month_day(), false))
+    /// Makes sure that neither part of the field is empty,
+    /// and that no plain day or modifier is one that
+    /// can't exist in any month.
+    pub fn check(&self) -> Result<(), Error> {
+        let (month, week) = self.parts();
+        if let Some(month) = month {
+            if month.is_empty() {
+                return Err(Error::EmptyRing(Field::DaysOfMonth));
+            }
+            if month.is_out_of_range() {
+                return Err(Error::OutOfRange(Field::DaysOfMonth));
             }
-            Days::Month(month) => hours_overflow
-                .then(|| month.checked_next().unwrap())
-                .unwrap_or((month.peek().unwrap(), false)),
-            Days::Week((week, summary)) => hours_overflow
-                .then(|| {
-                    let next_day_of_the_week = week.next().unwrap();
-                    let (next, overflow) = weekday_to_month_day(
-                        next_day_of_the_week,
-                        summary.last_weekday(),
-                        days_in_curr_month,
-                    );
-                    let week_summary = WeekSummary {
-                        last_month_day: next,
-                        last_weekday: next_day_of_the_week,
-                        last_used: LastUsed::Week,
-                    };
-                    summary.set(week_summary);
-                    (summary.last_month_day(), overflow)
-                })
-                .unwrap_or((summary.last_month_day(), false)),
         }
+        if let Some(week) = week {
+            if week.is_empty() {
+                return Err(Error::EmptyRing(Field::DaysOfWeek));
+            }
+            if week.is_out_of_range() {
+                return Err(Error::OutOfRange(Field::DaysOfWeek));
+            }
+        }
+        Ok(())
     }
 
-    fn next_day_of_the_month(
-        days: &mut CopyRing<u8>,
-        hours_overflow: bool,
-        day_of_month: u8,
-        days_in_curr_month: u8,
-    ) -> (u8, bool) {
-        let mut found = false;
-        days.reset();
-        for day in days.one_cycle() {
-            if day >= day_of_month {
-                found = true;
-                break;
+    /// Hands the modifiers over to the matching part of the field.
+    /// Fails if the modifiers were for a part that this field
+    /// doesn't have, like `5L` with `with_days_of_the_month_only`.
+    pub fn set_modifiers(
+        &mut self,
+        month_modifiers: Vec<MonthDayModifier>,
+        week_modifiers: Vec<WeekdayModifier>,
+    ) -> Result<(), Error> {
+        let (month, week) = match self {
+            Days::Both { month, week } => (Some(month), Some(week)),
+            Days::Month(month) => (Some(month), None),
+            Days::Week(week) => (None, Some(week)),
+        };
+        match month {
+            Some(month) => month.modifiers = month_modifiers,
+            None if !month_modifiers.is_empty() => {
+                return Err(Error::MissingField(Field::DaysOfMonth))
             }
+            None => {}
         }
-        if hours_overflow {
-            days.rotate_left(1)
-        }
-        if found {
-            days.rotate_right(1);
-            let next = days.peek().unwrap();
-            if next > days_in_curr_month {
-                days.reset();
-                (days.peek().unwrap(), true)
-            } else {
-                (next, false)
+        match week {
+            Some(week) => week.modifiers = week_modifiers,
+            None if !week_modifiers.is_empty() => {
+                return Err(Error::MissingField(Field::DaysOfWeek))
             }
-        } else {
-            (days.peek().unwrap(), true)
+            None => {}
         }
+        Ok(())
     }
+}
 
-    fn next_day_of_the_week(
-        days: &mut CopyRing<u8>,
-        hours_overflow: bool,
-        day_of_week: u8,
-        day_of_month: u8,
-        days_in_curr_month: u8,
-    ) -> (u8, bool) {
-        let mut found = false;
-        days.reset();
-        for weekday in days.one_cycle() {
-            if weekday >= day_of_week {
-                found = true;
-                break;
-            }
+impl MonthDays {
+    pub fn days(&self) -> Vec<u8> {
+        self.days.iter().collect()
+    }
+
+    pub fn modifiers(&self) -> &[MonthDayModifier] {
+        &self.modifiers
+    }
+
+    fn write(&self, out: &mut impl Write, format: Format) -> fmt::Result {
+        write_days(out, self.days.iter(), &self.modifiers, format)
+    }
+
+    fn in_month(&self, shape: MonthShape) -> Bits<u32> {
+        let mut days = self.days.intersection(shape.days());
+        for day in self
+            .modifiers
+            .iter()
+            .filter_map(|modifier| modifier.day_in(shape))
+        {
+            days.insert(day);
         }
-        if found {
-            days.rotate_right(1)
+        days
+    }
+
+    fn is_empty(&self) -> bool {
+        self.days.is_empty() && self.modifiers.is_empty()
+    }
+
+    /// Plain days that don't fit never make it into the bits,
+    /// so the builder checks those, and this checks the modifiers.
+    fn is_out_of_range(&self) -> bool {
+        self.days.contains(0)
+            || self.modifiers.iter().any(|modifier| match *modifier {
+                MonthDayModifier::Last { offset } => offset > 30,
+                MonthDayModifier::NearestWeekday(day) => !(1..=31).contains(&day),
+                MonthDayModifier::LastWeekday => false,
+            })
+    }
+}
+
+impl From<Bits<u32>> for MonthDays {
+    fn from(days: Bits<u32>) -> Self {
+        Self {
+            days,
+            modifiers: Vec::new(),
         }
-        if hours_overflow {
-            days.rotate_left(1)
+    }
+}
+
+impl WeekDays {
+    pub fn days(&self) -> Vec<u8> {
+        self.days.iter().collect()
+    }
+
+    pub fn modifiers(&self) -> &[WeekdayModifier] {
+        &self.modifiers
+    }
+
+    fn write(&self, out: &mut impl Write, format: Format) -> fmt::Result {
+        write_days(out, self.days.iter(), &self.modifiers, format)
+    }
+
+    /// Each weekday turns into every seventh day of the
+    /// month, starting from the first day that lands on it.
+    fn in_month(&self, shape: MonthShape) -> Bits<u32> {
+        let every_seventh: Bits<u32> = [0, 7, 14, 21, 28].into_iter().collect();
+        let mut days = self
+            .days
+            .iter()
+            .map(|weekday| every_seventh.shifted_up(shape.first(weekday)))
+            .fold(Bits::default(), Bits::union);
+        for day in self
+            .modifiers
+            .iter()
+            .filter_map(|modifier| modifier.day_in(shape))
+        {
+            days.insert(day);
         }
-        let weekday = days.peek().unwrap();
-        let days_since_now = Self::num_weekdays_since(day_of_week as i8, weekday as i8);
-        let next_day_unmodded = day_of_month + days_since_now;
-        if next_day_unmodded > days_in_curr_month {
-            (next_day_unmodded % days_in_curr_month, true)
-        } else {
-            (next_day_unmodded, false)
+        days.intersection(shape.days())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.days.is_empty() && self.modifiers.is_empty()
+    }
+
+    fn is_out_of_range(&self) -> bool {
+        self.days.contains(7)
+            || self.modifiers.iter().any(|modifier| match *modifier {
+                WeekdayModifier::Last(weekday) => weekday >= 7,
+                WeekdayModifier::Nth { weekday, nth } => weekday >= 7 || !(1..=5).contains(&nth),
+            })
+    }
+}
+
+impl From<Bits<u8>> for WeekDays {
+    fn from(days: Bits<u8>) -> Self {
+        Self {
+            days,
+            modifiers: Vec::new(),
         }
     }
+}
 
-    pub fn num_weekdays_since(first_weekday: i8, second_weekday: i8) -> u8 {
-        let days_in_a_week = 7i8;
-        let diff = days_in_a_week + second_weekday - first_weekday;
-        (diff % days_in_a_week) as u8
+impl fmt::Display for MonthDayModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MonthDayModifier::Last { offset: 0 } => write!(f, "L"),
+            MonthDayModifier::Last { offset } => write!(f, "L-{offset}"),
+            MonthDayModifier::NearestWeekday(day) => write!(f, "{day}W"),
+            MonthDayModifier::LastWeekday => write!(f, "LW"),
+        }
     }
 }
 
-/// # Currently doesn't work
-/// 
-/// Works if the next day is from the next month,
-/// but fails if the next day is in the previous month.
-/// 
-/// Even worse, this function doesn't factor in 
-/// what happens if the next day is more than one month
-/// away.
-pub(crate) fn next_weekday_from_last(
-    last_weekday: u8,
-    next_day_month: u8,
-    days_in_curr_month: u8,
-    last_day_of_month: u8,
-) -> u8 {
-    let days_in_a_week = 7;
-    (last_weekday + (next_day_month + days_in_curr_month - last_day_of_month)) % days_in_a_week
+impl fmt::Display for WeekdayModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WeekdayModifier::Last(weekday) => {
+                write!(f, "{}L", format::weekday_name(weekday as u32))
+            }
+            WeekdayModifier::Nth { weekday, nth } => {
+                write!(f, "{}#{nth}", format::weekday_name(weekday as u32))
+            }
+        }
+    }
 }
 
-impl Months {
-    pub fn new(copy_ring: CopyRing<u8>) -> Self {
-        Self(copy_ring)
-    }
-
-    pub fn first_after(&mut self, day_overflow: bool, month: u8) -> (u8, bool) {
-        let mut found = false;
-        let mut overflow = false;
-        self.0.reset();
-        for other_month in self.0.one_cycle().checked() {
-            if other_month.0 >= month {
-                found = true;
-                overflow = other_month.1;
-                break;
+impl MonthDayModifier {
+    /// Returns the day of the month that the modifier lands
+    /// on, or `None` if the month is too short for it.
+    fn day_in(self, shape: MonthShape) -> Option<u8> {
+        match self {
+            MonthDayModifier::Last { offset } => (offset < shape.len).then(|| shape.len - offset),
+            MonthDayModifier::NearestWeekday(target) => {
+                (target <= shape.len).then(|| nearest_weekday(target, shape))
             }
+            MonthDayModifier::LastWeekday => Some(nearest_weekday(shape.len, shape)),
         }
-        if day_overflow {
-            overflow = self.0.checked_next().unwrap().1 || overflow
+    }
+}
+
+impl WeekdayModifier {
+    /// Returns the day of the month that the modifier lands
+    /// on, or `None` if the month is too short for it.
+    fn day_in(self, shape: MonthShape) -> Option<u8> {
+        let days_in_a_week = 7;
+        match self {
+            WeekdayModifier::Last(weekday) => {
+                let first = shape.first(weekday);
+                Some(first + (shape.len - first) / days_in_a_week * days_in_a_week)
+            }
+            WeekdayModifier::Nth { weekday, nth } => {
+                let day = shape.first(weekday) + (nth - 1) * days_in_a_week;
+                (day <= shape.len).then_some(day)
+            }
         }
-        if found {
-            self.0.rotate_right(1);
-            let final_overflow = day_overflow && overflow;
-            (self.0.peek().unwrap(), final_overflow)
-        } else {
-            (self.0.peek().unwrap(), true)
+    }
+}
+
+/// How long a month is, and which weekday it starts on,
+/// which is all the day field needs to know about it.
+#[derive(Clone, Copy)]
+struct MonthShape {
+    len: u8,
+    first_weekday: u8,
+}
+
+impl MonthShape {
+    fn new(month: u8, year: u32) -> Self {
+        Self {
+            len: crate::days_in_a_month(month, year),
+            first_weekday: weekday(1, month, year),
         }
     }
 
-    pub fn next(&mut self, days_overflow: bool) -> (u8, bool) {
-        if days_overflow {
-            self.0.checked_next().unwrap()
-        } else {
-            (self.0.peek().unwrap(), false)
+    /// Returns every day of the month.
+    fn days(self) -> Bits<u32> {
+        Bits::range(1, self.len)
+    }
+
+    /// Returns the weekday that `day` lands on.
+    fn weekday(self, day: u8) -> u8 {
+        (self.first_weekday + day - 1) % 7
+    }
+
+    /// Returns the first day of the month that lands on `weekday`.
+    fn first(self, weekday: u8) -> u8 {
+        (weekday + 7 - self.first_weekday) % 7 + 1
+    }
+}
+
+/// Returns the weekday (Monday through Friday) closest to
+/// `target`, without leaving the month. A Saturday moves back
+/// to Friday and a Sunday moves up to Monday, unless that would
+/// cross into another month, in which case they jump over the
+/// weekend in the other direction.
+fn nearest_weekday(target: u8, shape: MonthShape) -> u8 {
+    match shape.weekday(target) {
+        6 if target == 1 => target + 2,
+        6 => target - 1,
+        0 if target == shape.len => target - 2,
+        0 => target + 1,
+        _ => target,
+    }
+}
+
+/// Returns the day of the week for the given date,
+/// where Sunday = 0 -----> Saturday = 6.
+///
+/// This gets worked out for every month that a search goes
+/// through, so it's done with Sakamoto's method rather than by
+/// building a `NaiveDate`. January and February are counted
+/// as the end of the year before, to keep leap days last. The
+/// weekdays repeat every `YEARS_IN_A_CYCLE` years, so the year
+/// is moved into the second cycle first, which keeps stepping
+/// back a year from overflowing either way.
+fn weekday(day: u8, month: u8, year: u32) -> u8 {
+    const MONTH_OFFSETS: [u32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let year = year % super::YEARS_IN_A_CYCLE + super::YEARS_IN_A_CYCLE - u32::from(month < 3);
+    let days =
+        year + year / 4 - year / 100 + year / 400 + MONTH_OFFSETS[month as usize - 1] + day as u32;
+    (days % 7) as u8
+}
+
+impl Months {
+    pub fn new(bits: Bits<u16>) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the earliest month that is equal to
+    /// or later than `month`, or `None` if the year
+    /// runs out before one is found.
+    pub fn first_from(&self, month: u8) -> Option<u8> {
+        self.0.first_from(month)
+    }
+
+    /// Returns the latest month that is equal to
+    /// or earlier than `month`, or `None` if the year
+    /// runs out before one is found.
+    pub fn last_until(&self, month: u8) -> Option<u8> {
+        self.0.last_until(month)
+    }
+
+    pub fn contains(&self, month: u8) -> bool {
+        self.0.contains(month)
+    }
+
+    pub fn values(&self) -> Vec<u8> {
+        self.0.iter().collect()
+    }
+}
+
+impl Years {
+    pub fn new(mut years: Vec<u32>) -> Self {
+        years.sort_unstable();
+        years.dedup();
+        Self(Some(years))
+    }
+
+    /// Returns the earliest year that is equal to or later
+    /// than `year`, or `None` if the schedule has already
+    /// passed its last year.
+    pub fn first_from(&self, year: u32) -> Option<u32> {
+        match self.0 {
+            Some(ref years) => years.get(years.partition_point(|&y| y < year)).copied(),
+            None => Some(year),
+        }
+    }
+
+    /// Returns the latest year that is equal to or earlier
+    /// than `year`, or `None` if the schedule hasn't
+    /// reached its first year yet.
+    pub fn last_until(&self, year: u32) -> Option<u32> {
+        match self.0 {
+            Some(ref years) => {
+                let after = years.partition_point(|&y| y <= year);
+                after.checked_sub(1).map(|index| years[index])
+            }
+            None => Some(year),
+        }
+    }
+
+    pub fn contains(&self, year: u32) -> bool {
+        self.0
+            .as_ref()
+            .is_none_or(|years| years.binary_search(&year).is_ok())
+    }
+
+    /// Returns the years that the schedule can run in,
+    /// or `None` if it can run in any year.
+    pub fn values(&self) -> Option<&[u32]> {
+        self.0.as_deref()
+    }
+}
+
+/// Writes out one part of the day field, with the plain days
+/// first and the modifiers after them in a fixed order.
+fn write_days<M: Copy + Ord + fmt::Display>(
+    out: &mut impl Write,
+    days: impl Iterator<Item = u8>,
+    modifiers: &[M],
+    format: Format,
+) -> fmt::Result {
+    let days: Vec<u32> = days.map(|day| day as u32).collect();
+    let mut modifiers = modifiers.to_vec();
+    modifiers.sort();
+    modifiers.dedup();
+    format.write(out, &days)?;
+    if !days.is_empty() && !modifiers.is_empty() {
+        out.write_char(',')?;
+    }
+    format::write_list(out, modifiers)
+}
+
+/// Writes out a field that is kept as bits.
+fn write_bits<T: Word>(f: &mut fmt::Formatter<'_>, bits: Bits<T>, format: Format) -> fmt::Result {
+    let values: Vec<u32> = bits.iter().map(|value| value as u32).collect();
+    format.write(f, &values)
+}
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bits(f, self.0, Format::number(0, 59))
+    }
+}
+
+impl fmt::Display for Minutes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bits(f, self.0, Format::number(0, 59))
+    }
+}
+
+impl fmt::Display for Hours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bits(f, self.0, Format::number(0, 23))
+    }
+}
+
+impl fmt::Display for Months {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bits(f, self.0, Format::months())
+    }
+}
+
+/// Writes out the years as a field of their own, or
+/// nothing at all when there's no restriction on them.
+impl fmt::Display for Years {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(ref years) => Format::number(super::FIRST_YEAR, super::LAST_YEAR)
+                .without_wildcard()
+                .write(f, years.as_slice()),
+            None => Ok(()),
         }
     }
 }
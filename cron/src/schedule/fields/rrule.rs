@@ -0,0 +1,93 @@
+//! Writes a `FieldTable` out as an iCalendar (RFC 5545) `RRULE`,
+//! like `FREQ=WEEKLY;BYDAY=MO,FR;BYHOUR=9;BYMINUTE=0;BYSECOND=0`.
+
+use super::{
+    inner::{MonthDays, WeekDays},
+    FieldTable, MonthDayModifier, WeekdayModifier,
+};
+use crate::schedule::rrule::RULE_WEEKDAYS;
+
+impl FieldTable {
+    /// Writes the table as the value of an `RRULE`, in the same
+    /// local time that the table is read in. Every time of day
+    /// is listed out, since a rule takes anything it leaves out
+    /// from the start of the event instead.
+    ///
+    /// Returns `None` for tables that a rule can't describe:
+    /// ones with restricted years, ones that use both day fields,
+    /// since a rule only runs on days that match both, and ones
+    /// with `W` days, which rules have no way of writing.
+    pub fn to_rrule(&self) -> Option<String> {
+        if self.years.values().is_some() {
+            return None;
+        }
+        let (freq, days) = match self.days.parts() {
+            (Some(month), None) => month_days(month)?,
+            (None, Some(week)) => week_days(week),
+            _ => return None,
+        };
+
+        let mut parts = vec![format!("FREQ={freq}")];
+        let months = self.months.values();
+        if months.len() < 12 {
+            parts.push(format!("BYMONTH={}", list(months)));
+        }
+        parts.extend(days);
+        parts.push(format!("BYHOUR={}", list(self.hours.values())));
+        parts.push(format!("BYMINUTE={}", list(self.mins.values())));
+        parts.push(format!("BYSECOND={}", list(self.secs.values())));
+        Some(parts.join(";"))
+    }
+}
+
+/// Picks the frequency for the days of the month, and writes
+/// them out as a `BYMONTHDAY`, where days counted from the end
+/// of the month are negative. Every day of the month is
+/// just a `DAILY` rule without any.
+fn month_days(month: &MonthDays) -> Option<(&'static str, Option<String>)> {
+    let (days, modifiers) = (month.days(), month.modifiers());
+    if days.len() == 31 && modifiers.is_empty() {
+        return Some(("DAILY", None));
+    }
+    let mut values: Vec<String> = days.iter().map(u8::to_string).collect();
+    for modifier in modifiers {
+        match *modifier {
+            MonthDayModifier::Last { offset } => values.push(format!("-{}", offset + 1)),
+            MonthDayModifier::NearestWeekday(_) | MonthDayModifier::LastWeekday => return None,
+        }
+    }
+    Some(("MONTHLY", Some(format!("BYMONTHDAY={}", values.join(",")))))
+}
+
+/// Picks the frequency for the days of the week, and writes them
+/// out as a `BYDAY`. Days like `5L` and `1#2` only count once a
+/// month, so they're written like `-1FR` and `2MO` in a
+/// `MONTHLY` rule, and plain weekdays go in a `WEEKLY` one.
+fn week_days(week: &WeekDays) -> (&'static str, Option<String>) {
+    let (days, modifiers) = (week.days(), week.modifiers());
+    if days.len() == 7 && modifiers.is_empty() {
+        return ("DAILY", None);
+    }
+    let mut values: Vec<String> = days
+        .iter()
+        .map(|&day| RULE_WEEKDAYS[day as usize].to_owned())
+        .collect();
+    for modifier in modifiers {
+        values.push(match *modifier {
+            WeekdayModifier::Last(day) => format!("-1{}", RULE_WEEKDAYS[day as usize]),
+            WeekdayModifier::Nth { weekday, nth } => {
+                format!("{nth}{}", RULE_WEEKDAYS[weekday as usize])
+            }
+        });
+    }
+    let freq = match modifiers.is_empty() {
+        true => "WEEKLY",
+        false => "MONTHLY",
+    };
+    (freq, Some(format!("BYDAY={}", values.join(","))))
+}
+
+fn list(values: Vec<u8>) -> String {
+    let values: Vec<String> = values.iter().map(u8::to_string).collect();
+    values.join(",")
+}
@@ -1,6 +1,10 @@
-use super::{Days, Minutes, Seconds, inner::{Hours, next_weekday_from_last}};
-use crate::schedule::iterator::CopyRing;
-use chrono::{Datelike, Timelike, Utc};
+use super::{
+    bits::Bits,
+    inner::{Hours, Years},
+    Days, Error, FieldTable, Minutes, MonthDayModifier, Seconds, WeekdayModifier,
+};
+use crate::Field;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
 use rand::Rng;
 
 const THRESHOLD: i32 = 50;
@@ -15,7 +19,7 @@ fn gen_range_days_of_month() -> Vec<u8> {
         }
     }
     if v.is_empty() {
-        v.push(rng.gen::<u8>() % 31)
+        v.push(rng.gen::<u8>() % 31 + 1)
     }
     v
 }
@@ -62,116 +66,621 @@ fn gen_range_hours() -> Vec<u8> {
     v
 }
 
+fn datetime(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .unwrap()
+        .and_hms_opt(hour, min, sec)
+        .unwrap()
+}
+
+/// The schedule that `routedatacollect` runs on,
+/// "00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri".
+fn route_table() -> FieldTable {
+    FieldTable::builder()
+        .with_secs(0)
+        .with_mins(16)
+        .with_hrs_iter(13..=18)
+        .with_days_of_the_week_only_iter(1..=5)
+        .with_months_iter(1..=12)
+        .build()
+        .unwrap()
+}
+
 #[test]
-fn num_weekdays_since_returns_correct_day() {
-    let sun_to_fri = Days::num_weekdays_since(0, 5);
-    assert_eq!(5, sun_to_fri);
+fn first_from_works_for_secs() {
+    let seconds = Seconds::new(gen_range_mins_or_secs().into_iter().collect());
+    let now = Utc::now();
 
-    let fri_to_sun = Days::num_weekdays_since(5, 0);
-    assert_eq!(2, fri_to_sun);
+    match seconds.first_from(now.second() as u8) {
+        Some(next) => assert!((next as u32) >= now.second()),
+        None => assert!(seconds.first_from(0).unwrap() < now.second() as u8),
+    }
+}
 
-    let wed_to_tues = Days::num_weekdays_since(3, 2);
-    assert_eq!(6, wed_to_tues);
+#[test]
+fn first_from_works_for_mins() {
+    let values = gen_range_mins_or_secs();
+    let minutes = Minutes::new(values.iter().copied().collect());
+    for i in 0..60 {
+        let expected = values.iter().copied().find(|&min| min >= i);
+        assert_eq!(expected, minutes.first_from(i));
+    }
+}
 
-    let thurs_to_thurs = Days::num_weekdays_since(4, 4);
-    assert_eq!(0, thurs_to_thurs);
+#[test]
+fn first_from_works_for_hours() {
+    let values = gen_range_hours();
+    let hours = Hours::new(values.iter().copied().collect());
+    for i in 0..24 {
+        let expected = values.iter().copied().find(|&hour| hour >= i);
+        assert_eq!(expected, hours.first_from(i));
+    }
 }
 
 #[test]
-fn first_after_works_for_secs() {
-    let mut seconds = Seconds::new(CopyRing::from(gen_range_mins_or_secs()));
-    let now = Utc::now();
+fn first_from_days_both_spec() {
+    let week = gen_range_days_of_week();
+    let month = gen_range_days_of_month();
+    let days = Days::Both {
+        week: week.iter().copied().collect::<Bits<_>>().into(),
+        month: month.iter().copied().collect::<Bits<_>>().into(),
+    };
 
-    let next = seconds.first_after(now.second() as u8);
-    match next.1 {
-        true => assert!((next.0 as u32) < now.second()),
-        false => assert!((next.0 as u32) >= now.second()),
+    // June 2023 starts on a Thursday.
+    for day in 1..=30 {
+        let next = days.first_from(day, 6, 2023);
+        let expected = (day..=30).find(|&d| month.contains(&d) || week.contains(&((d + 3) % 7)));
+        assert_eq!(expected, next, "days: {days:?}, from June {day}");
     }
 }
 
 #[test]
-fn next_weekday_from_last_works() {
-    let now = (2, 13);
-    let next = next_weekday_from_last(now.0, 15, 30, now.1);
+fn first_from_days_respects_month_length() {
+    let days = Days::Month([29, 30, 31].into_iter().collect::<Bits<_>>().into());
 
-    assert_eq!(4, next);
+    assert_eq!(None, days.first_from(1, 2, 2023));
+    assert_eq!(Some(29), days.first_from(1, 2, 2024));
+    assert_eq!(Some(30), days.first_from(30, 4, 2024));
+    assert_eq!(None, days.first_from(31, 4, 2024));
 }
 
 #[test]
-fn first_after_works_for_mins_no_overflow() {
-    let mut minutes = Minutes::new(CopyRing::from(gen_range_mins_or_secs()));
-    let now = Utc::now();
+fn first_from_years() {
+    let unbounded = Years::default();
+    assert_eq!(Some(2023), unbounded.first_from(2023));
 
-    let next = minutes.first_after(now.minute() as u8, false);
-    match next.1 {
-        true => assert!((next.0 as u32) < now.minute()),
-        false => assert!((next.0 as u32) >= now.minute()),
-    }
+    let bounded = Years::new(vec![2023, 2025]);
+    assert_eq!(Some(2023), bounded.first_from(2020));
+    assert_eq!(Some(2025), bounded.first_from(2024));
+    assert_eq!(None, bounded.first_from(2026));
 }
 
 #[test]
-fn first_after_works_for_mins_overflow() {
-    let mut minutes = Minutes::new(CopyRing::from(gen_range_mins_or_secs()));
-    for i in 0..60 {
-        let now2 = i;
-
-        let next = minutes.first_after(now2, true);
-        eprintln!("now: {} minutes", now2);
-        //dbg!(next);
-        //dbg!(&minutes);
-        match next.1 {
-            true => assert!((next.0) < now2),
-            false => assert!((next.0) >= now2),
+fn after_is_strictly_after() {
+    let table = route_table();
+    // Monday, June 5th 2023
+    let start = datetime(2023, 6, 5, 13, 16, 0);
+
+    assert_eq!(Some(datetime(2023, 6, 5, 14, 16, 0)), table.after(&start));
+}
+
+#[test]
+fn after_skips_the_weekend() {
+    let table = route_table();
+    // Friday, June 9th 2023
+    let start = datetime(2023, 6, 9, 18, 16, 0);
+
+    assert_eq!(Some(datetime(2023, 6, 12, 13, 16, 0)), table.after(&start));
+}
+
+#[test]
+fn after_resets_lower_fields() {
+    let table = FieldTable::builder()
+        .with_secs_iter([10, 20])
+        .with_mins_iter([5, 30])
+        .with_hrs_iter([1, 12])
+        .with_days_of_the_month_only_iter(1..=31)
+        .with_months_iter(1..=12)
+        .build()
+        .unwrap();
+
+    let start = datetime(2023, 6, 5, 1, 30, 25);
+    assert_eq!(Some(datetime(2023, 6, 5, 12, 5, 10)), table.after(&start));
+
+    let start = datetime(2023, 12, 31, 12, 30, 20);
+    assert_eq!(Some(datetime(2024, 1, 1, 1, 5, 10)), table.after(&start));
+}
+
+#[test]
+fn after_finds_leap_days() {
+    let table = FieldTable::builder()
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([0])
+        .with_days_of_the_month_only(29)
+        .with_months_iter([2])
+        .build()
+        .unwrap();
+
+    let start = datetime(2093, 3, 1, 0, 0, 0);
+    assert_eq!(Some(datetime(2096, 2, 29, 0, 0, 0)), table.after(&start));
+
+    // 2100 isn't a leap year, and the next one is past the last year.
+    let start = datetime(2096, 3, 1, 0, 0, 0);
+    assert_eq!(None, table.after(&start));
+}
+
+#[test]
+fn build_turns_down_days_that_never_happen() {
+    let build = |days: u8, months: &[u8], years: Option<&[u32]>| {
+        let mut builder = FieldTable::builder();
+        builder
+            .with_secs(0)
+            .with_mins(0)
+            .with_hrs(0)
+            .with_days_of_the_month_only(days)
+            .with_months_iter(months.iter().copied());
+        if let Some(years) = years {
+            builder.with_years_iter(years.iter().copied());
         }
-    }
+        builder.build()
+    };
+
+    assert!(matches!(build(30, &[2], None), Err(Error::NeverRuns)));
+    assert!(matches!(
+        build(31, &[4, 6, 9, 11], None),
+        Err(Error::NeverRuns)
+    ));
+    assert!(matches!(
+        build(29, &[2], Some(&[2025, 2026, 2027])),
+        Err(Error::NeverRuns)
+    ));
+    assert!(build(29, &[2], Some(&[2025, 2028])).is_ok());
+    assert!(build(31, &[4, 6, 9, 11, 12], None).is_ok());
+}
+
+#[test]
+fn after_jumps_to_first_year() {
+    let table = FieldTable::builder()
+        .with_secs(0)
+        .with_mins(16)
+        .with_hrs_iter(13..=18)
+        .with_days_of_the_week_only_iter(1..=5)
+        .with_months_iter(1..=12)
+        .with_years_iter(2030..=2031)
+        .build()
+        .unwrap();
+
+    let start = datetime(2023, 6, 5, 14, 0, 0);
+    // January 1st, 2030 is a Tuesday
+    assert_eq!(Some(datetime(2030, 1, 1, 13, 16, 0)), table.after(&start));
+}
+
+#[test]
+fn after_ends_after_last_year() {
+    let table = FieldTable::builder()
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([0])
+        .with_days_of_the_month_only(1)
+        .with_months_iter([1])
+        .with_years_iter([2023, 2024])
+        .build()
+        .unwrap();
+
+    let start = datetime(2023, 1, 1, 0, 0, 0);
+    assert_eq!(Some(datetime(2024, 1, 1, 0, 0, 0)), table.after(&start));
+
+    let start = datetime(2024, 1, 1, 0, 0, 0);
+    assert_eq!(None, table.after(&start));
+}
+
+#[test]
+fn searches_stay_inside_the_years_a_schedule_runs_in() {
+    let table = route_table();
+    let ancient = datetime(-5, 3, 1, 12, 0, 0);
+    let year_zero = datetime(0, 1, 1, 0, 0, 0);
+    let far_future = datetime(2150, 6, 1, 0, 0, 0);
+
+    // Thursday, January 1st 1970
+    assert_eq!(Some(datetime(1970, 1, 1, 13, 16, 0)), table.after(&ancient));
+    assert_eq!(
+        Some(datetime(1970, 1, 1, 13, 16, 0)),
+        table.after(&year_zero)
+    );
+    assert_eq!(None, table.after(&far_future));
+
+    assert_eq!(None, table.before(&ancient));
+    // Thursday, December 31st 2099
+    assert_eq!(
+        Some(datetime(2099, 12, 31, 18, 16, 0)),
+        table.before(&far_future)
+    );
+
+    assert!(!table.contains(&datetime(-5, 3, 1, 13, 16, 0)));
+    assert!(!table.contains(&datetime(2150, 6, 1, 13, 16, 0)));
+    assert_eq!(0, table.count(&ancient, &year_zero));
+    assert_eq!(
+        table.count(
+            &datetime(1970, 1, 1, 0, 0, 0),
+            &datetime(1970, 2, 1, 0, 0, 0)
+        ),
+        table.count(&ancient, &datetime(1970, 2, 1, 0, 0, 0))
+    );
+    assert_eq!(
+        Some(datetime(1970, 1, 2, 13, 16, 0)),
+        table.nth_from(&ancient, 7)
+    );
 }
 
 #[test]
-fn first_after_works_for_hours_overflow() {
-    let mut hours = Hours::new(CopyRing::from(gen_range_hours()));
+fn last_until_works_for_hours() {
+    let values = gen_range_hours();
+    let hours = Hours::new(values.iter().copied().collect());
     for i in 0..24 {
-        let now2 = i;
-
-        let next = hours.first_after(now2, true);
-        eprintln!("now: {} hours", now2);
-        //dbg!(next);
-        //dbg!(&hours);
-        match next.1 {
-            true => assert!((next.0) < now2),
-            false => assert!((next.0) >= now2),
-        }
+        let expected = values.iter().copied().rev().find(|&hour| hour <= i);
+        assert_eq!(expected, hours.last_until(i));
     }
 }
 
 #[test]
-fn first_after_days_both_spec() {
-    let mut days = Days::Both {
-        week: (
-            CopyRing::from(gen_range_days_of_week()),
-            Default::default(),
-        ),
-        month: CopyRing::from(gen_range_days_of_month()),
+fn last_until_days_respects_month_length() {
+    let days = Days::Month([1, 29, 30, 31].into_iter().collect::<Bits<_>>().into());
+
+    assert_eq!(Some(1), days.last_until(31, 2, 2023));
+    assert_eq!(Some(29), days.last_until(31, 2, 2024));
+    assert_eq!(Some(30), days.last_until(31, 4, 2024));
+    assert_eq!(None, days.last_until(0, 4, 2024));
+}
+
+#[test]
+fn last_until_years() {
+    let bounded = Years::new(vec![2023, 2025]);
+    assert_eq!(None, bounded.last_until(2020));
+    assert_eq!(Some(2023), bounded.last_until(2024));
+    assert_eq!(Some(2025), bounded.last_until(2026));
+}
+
+#[test]
+fn before_is_strictly_before() {
+    let table = route_table();
+    // Monday, June 5th 2023
+    let end = datetime(2023, 6, 5, 14, 16, 0);
+    assert_eq!(Some(datetime(2023, 6, 5, 13, 16, 0)), table.before(&end));
+
+    let end = end + chrono::Duration::milliseconds(1);
+    assert_eq!(Some(datetime(2023, 6, 5, 14, 16, 0)), table.before(&end));
+}
+
+#[test]
+fn before_skips_the_weekend() {
+    let table = route_table();
+    // Monday, June 12th 2023
+    let end = datetime(2023, 6, 12, 13, 16, 0);
+
+    assert_eq!(Some(datetime(2023, 6, 9, 18, 16, 0)), table.before(&end));
+}
+
+#[test]
+fn before_sets_lower_fields_to_their_last_value() {
+    let table = FieldTable::builder()
+        .with_secs_iter([10, 20])
+        .with_mins_iter([5, 30])
+        .with_hrs_iter([1, 12])
+        .with_days_of_the_month_only_iter(1..=31)
+        .with_months_iter(1..=12)
+        .build()
+        .unwrap();
+
+    let end = datetime(2023, 6, 5, 12, 5, 5);
+    assert_eq!(Some(datetime(2023, 6, 5, 1, 30, 20)), table.before(&end));
+
+    let end = datetime(2024, 1, 1, 0, 0, 0);
+    assert_eq!(Some(datetime(2023, 12, 31, 12, 30, 20)), table.before(&end));
+}
+
+#[test]
+fn before_wraps_around_midnight() {
+    let table = FieldTable::builder()
+        .with_secs_iter([30])
+        .with_mins_iter(0..60)
+        .with_hrs_iter(0..24)
+        .with_days_of_the_month_only_iter(1..=31)
+        .with_months_iter(1..=12)
+        .build()
+        .unwrap();
+
+    let end = datetime(2023, 3, 1, 0, 0, 10);
+    assert_eq!(Some(datetime(2023, 2, 28, 23, 59, 30)), table.before(&end));
+}
+
+#[test]
+fn before_finds_leap_days() {
+    let table = FieldTable::builder()
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([0])
+        .with_days_of_the_month_only(29)
+        .with_months_iter([2])
+        .build()
+        .unwrap();
+
+    let end = datetime(2104, 2, 28, 0, 0, 0);
+    assert_eq!(Some(datetime(2096, 2, 29, 0, 0, 0)), table.before(&end));
+}
+
+#[test]
+fn before_ends_before_first_year() {
+    let table = FieldTable::builder()
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([0])
+        .with_days_of_the_month_only(1)
+        .with_months_iter([1])
+        .with_years_iter([2023, 2024])
+        .build()
+        .unwrap();
+
+    let end = datetime(2030, 6, 1, 0, 0, 0);
+    assert_eq!(Some(datetime(2024, 1, 1, 0, 0, 0)), table.before(&end));
+
+    let end = datetime(2023, 1, 1, 0, 0, 0);
+    assert_eq!(None, table.before(&end));
+}
+
+#[test]
+fn before_undoes_after() {
+    let table = FieldTable::builder()
+        .with_secs_iter(gen_range_mins_or_secs())
+        .with_mins_iter(gen_range_mins_or_secs())
+        .with_hrs_iter(gen_range_hours())
+        .with_days_of_both_iter(gen_range_days_of_week(), gen_range_days_of_month())
+        .with_months_iter(1..=12)
+        .build()
+        .unwrap();
+
+    let start = datetime(2023, 1, 1, 0, 0, 0);
+    let mut prev = table.after(&start).unwrap();
+    for _ in 0..200 {
+        let next = table.after(&prev).unwrap();
+        assert_eq!(Some(prev), table.before(&next), "{table:?}");
+        prev = next;
+    }
+}
+
+#[test]
+fn years_out_of_range() {
+    let mut builder = FieldTable::builder();
+    builder
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([0])
+        .with_days_of_the_month_only(1)
+        .with_months_iter([1]);
+
+    assert!(matches!(
+        builder.with_years_iter([1969]).build(),
+        Err(Error::OutOfRange(Field::Years))
+    ));
+}
+
+#[test]
+fn contains_checks_each_field() {
+    let table = route_table();
+
+    // Monday, June 5th 2023
+    assert!(table.contains(&datetime(2023, 6, 5, 13, 16, 0)));
+    assert!(table.contains(&datetime(2023, 6, 5, 18, 16, 0)));
+    assert!(!table.contains(&datetime(2023, 6, 5, 12, 16, 0)));
+    assert!(!table.contains(&datetime(2023, 6, 5, 13, 17, 0)));
+    assert!(!table.contains(&datetime(2023, 6, 5, 13, 16, 30)));
+    assert!(!table.contains(&datetime(2023, 6, 4, 13, 16, 0)));
+}
+
+#[test]
+fn contains_agrees_with_after() {
+    let table = route_table();
+    let mut start = datetime(2023, 6, 1, 0, 0, 0);
+    for _ in 0..50 {
+        let next = table.after(&start).unwrap();
+        assert!(table.contains(&next));
+        assert!(!table.contains(&(next - chrono::Duration::minutes(1))));
+        start = next;
+    }
+}
+
+#[test]
+fn errors_name_the_field() {
+    let mut builder = FieldTable::builder();
+    builder
+        .with_secs(0)
+        .with_mins(0)
+        .with_days_of_the_month_only(1);
+    assert!(matches!(
+        builder.build(),
+        Err(Error::MissingField(Field::Hours))
+    ));
+
+    let mut builder = FieldTable::builder();
+    builder
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([0])
+        .with_days_of_the_week_only_iter([])
+        .with_months_iter(1..=12);
+    assert!(matches!(
+        builder.build(),
+        Err(Error::EmptyRing(Field::DaysOfWeek))
+    ));
+
+    let mut builder = FieldTable::builder();
+    builder
+        .with_secs(0)
+        .with_mins(60)
+        .with_hrs_iter([0])
+        .with_days_of_the_month_only(1)
+        .with_months_iter(1..=12);
+    assert!(matches!(
+        builder.build(),
+        Err(Error::OutOfRange(Field::Minutes))
+    ));
+}
+
+fn table_with_modifiers(month: Vec<MonthDayModifier>, week: Vec<WeekdayModifier>) -> FieldTable {
+    let mut builder = FieldTable::builder();
+    builder
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([9])
+        .with_months_iter(1..=12);
+    match (month.is_empty(), week.is_empty()) {
+        (false, true) => builder.with_days_of_the_month_only_iter([]),
+        (true, false) => builder.with_days_of_the_week_only_iter([]),
+        _ => builder.with_days_of_both_iter([], []),
     };
+    builder
+        .with_days_of_the_month_modifiers(month)
+        .with_days_of_the_week_modifiers(week)
+        .build()
+        .unwrap()
+}
 
-    let now = Utc::now();
-    let next = days.first_after(
-        now.day() as u8,
-        now.weekday().num_days_from_sunday() as u8,
-        true,
-        now.month() as u8,
-        now.year() as u32,
+/// Collects every date in the given year that the table
+/// lands on, as `(month, day)` pairs.
+fn dates_in_year(table: &FieldTable, year: i32) -> Vec<(u32, u32)> {
+    let mut dates = vec![];
+    let mut start = datetime(year - 1, 12, 31, 23, 59, 59);
+    while let Some(next) = table.after(&start) {
+        if next.year() != year {
+            break;
+        }
+        dates.push((next.month(), next.day()));
+        start = next;
+    }
+    dates
+}
+
+#[test]
+fn last_day_of_the_month() {
+    let table = table_with_modifiers(vec![MonthDayModifier::Last { offset: 0 }], vec![]);
+
+    let dates = dates_in_year(&table, 2024);
+    assert_eq!(12, dates.len());
+    assert_eq!((1, 31), dates[0]);
+    assert_eq!((2, 29), dates[1]);
+    assert_eq!((4, 30), dates[3]);
+
+    assert_eq!((2, 28), dates_in_year(&table, 2023)[1]);
+}
+
+#[test]
+fn days_before_the_last_day_of_the_month() {
+    let table = table_with_modifiers(vec![MonthDayModifier::Last { offset: 2 }], vec![]);
+
+    let dates = dates_in_year(&table, 2023);
+    assert_eq!((1, 29), dates[0]);
+    assert_eq!((2, 26), dates[1]);
+    assert_eq!((4, 28), dates[3]);
+}
+
+#[test]
+fn nearest_weekday() {
+    let table = table_with_modifiers(
+        vec![
+            MonthDayModifier::NearestWeekday(1),
+            MonthDayModifier::NearestWeekday(15),
+        ],
+        vec![],
     );
 
-    dbg!(days);
-    eprintln!("{}, {:?}", now, next);
+    let dates = dates_in_year(&table, 2023);
+    // Sunday the 1st moves up to Monday the 2nd.
+    assert_eq!((1, 2), dates[0]);
+    // Sunday the 15th moves up to Monday the 16th.
+    assert_eq!((1, 16), dates[1]);
+    // Saturday the 1st jumps over the weekend to Monday the 3rd.
+    assert_eq!((4, 3), dates[6]);
+    // Saturday the 15th moves back to Friday the 14th.
+    assert_eq!((4, 14), dates[7]);
+    // A weekday stays put.
+    assert_eq!((6, 1), dates[10]);
 }
 
 #[test]
-fn next_for_seconds() {
-    let mut secs = Seconds::new(CopyRing::from(gen_range_mins_or_secs()));
-    let mut rng = rand::thread_rng();
-    let s = rng.gen::<u8>() % 60;
-    let first = secs.first_after(s);
-    eprintln!("First after {} seconds: {:?}", s, first);
-    dbg!(secs.next());
+fn nearest_weekday_skips_months_without_the_day() {
+    let table = table_with_modifiers(vec![MonthDayModifier::NearestWeekday(31)], vec![]);
+
+    let dates = dates_in_year(&table, 2023);
+    assert_eq!(7, dates.len());
+    // Sunday, December 31st can't move up into January.
+    assert_eq!((12, 29), dates[6]);
+}
+
+#[test]
+fn last_weekday_of_the_month() {
+    let table = table_with_modifiers(vec![MonthDayModifier::LastWeekday], vec![]);
+
+    let dates = dates_in_year(&table, 2023);
+    assert_eq!(
+        vec![
+            (1, 31),
+            (2, 28),
+            (3, 31),
+            (4, 28),
+            (5, 31),
+            (6, 30),
+            (7, 31),
+            (8, 31),
+            (9, 29),
+            (10, 31),
+            (11, 30),
+            (12, 29)
+        ],
+        dates
+    );
+}
+
+#[test]
+fn last_friday_of_the_month() {
+    let table = table_with_modifiers(vec![], vec![WeekdayModifier::Last(5)]);
+
+    let dates = dates_in_year(&table, 2023);
+    assert_eq!(12, dates.len());
+    assert_eq!((1, 27), dates[0]);
+    assert_eq!((2, 24), dates[1]);
+    assert_eq!((3, 31), dates[2]);
+    assert_eq!((6, 30), dates[5]);
+}
+
+#[test]
+fn nth_weekday_of_the_month() {
+    let first_monday =
+        table_with_modifiers(vec![], vec![WeekdayModifier::Nth { weekday: 1, nth: 1 }]);
+    let dates = dates_in_year(&first_monday, 2023);
+    assert_eq!(12, dates.len());
+    assert_eq!((1, 2), dates[0]);
+    assert_eq!((5, 1), dates[4]);
+
+    // Only some months have a fifth Tuesday.
+    let fifth_tuesday =
+        table_with_modifiers(vec![], vec![WeekdayModifier::Nth { weekday: 2, nth: 5 }]);
+    let dates = dates_in_year(&fifth_tuesday, 2023);
+    assert_eq!(vec![(1, 31), (5, 30), (8, 29), (10, 31)], dates);
+}
+
+#[test]
+fn modifiers_without_a_matching_day_field() {
+    let mut builder = FieldTable::builder();
+    builder
+        .with_secs(0)
+        .with_mins(0)
+        .with_hrs_iter([0])
+        .with_days_of_the_month_only_iter(1..=31)
+        .with_days_of_the_week_modifiers([WeekdayModifier::Last(5)])
+        .with_months_iter(1..=12);
+
+    assert!(matches!(
+        builder.build(),
+        Err(Error::MissingField(Field::DaysOfWeek))
+    ));
 }
@@ -0,0 +1,143 @@
+use crate::Error;
+use chrono::{DateTime, Duration, TimeZone};
+use std::{fmt, str::FromStr};
+
+/// A schedule that fires over and over, a fixed amount
+/// of time apart, written as `@every 1h30m`.
+///
+/// Unlike the field-based schedules, an interval doesn't
+/// line up with the clock. It counts from whenever the
+/// schedule was started, in absolute time, so changes
+/// to the UTC offset don't stretch or shrink it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Interval {
+    every: Duration,
+}
+
+impl Interval {
+    /// Returns the first datetime that comes one
+    /// interval after `date_time`.
+    pub fn after<Tz: TimeZone>(&self, date_time: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        date_time.clone().checked_add_signed(self.every)
+    }
+
+    /// Returns the datetime that comes one
+    /// interval before `date_time`.
+    pub fn before<Tz: TimeZone>(&self, date_time: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        date_time.clone().checked_sub_signed(self.every)
+    }
+
+    /// Returns how many intervals fit after `start`, up
+    /// to and including `end`.
+    pub fn count_between<Tz: TimeZone>(&self, start: &DateTime<Tz>, end: &DateTime<Tz>) -> u64 {
+        // Intervals are whole seconds, so anything
+        // under a second can't fit another one in.
+        let span = (end.clone() - start.clone()).num_seconds();
+        (span.max(0) / self.every.num_seconds()) as u64
+    }
+
+    /// Returns the datetime that comes `n`
+    /// intervals after `start`.
+    pub fn nth_after<Tz: TimeZone>(&self, start: &DateTime<Tz>, n: u64) -> Option<DateTime<Tz>> {
+        let seconds = i64::try_from(n)
+            .ok()?
+            .checked_mul(self.every.num_seconds())
+            .filter(|seconds| *seconds <= i64::MAX / 1_000)?;
+        start.clone().checked_add_signed(Duration::seconds(seconds))
+    }
+
+    /// Returns true if every time this interval fires, `other`
+    /// fires too, when both start at the same moment. That's
+    /// when this interval is a whole number of `other`s.
+    pub fn is_subset_of(&self, other: &Interval) -> bool {
+        self.every.num_seconds() % other.every.num_seconds() == 0
+    }
+
+    /// Describes the interval in English, like
+    /// "Every 1 hour and 30 minutes".
+    pub fn describe(&self) -> String {
+        let mut rest = self.every.num_seconds();
+        let mut parts = Vec::new();
+        for ((_, seconds), name) in UNITS.into_iter().zip(UNIT_NAMES) {
+            let count = rest / seconds;
+            rest %= seconds;
+            match count {
+                0 => {}
+                1 => parts.push(format!("1 {name}")),
+                _ => parts.push(format!("{count} {name}s")),
+            }
+        }
+        match parts.pop() {
+            Some(last) if parts.is_empty() => format!("Every {last}"),
+            Some(last) => format!("Every {} and {last}", parts.join(", ")),
+            None => "Every 0 seconds".to_owned(),
+        }
+    }
+}
+
+/// The units that an interval can be written in,
+/// from largest to smallest, in seconds.
+const UNITS: [(char, i64); 4] = [('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)];
+
+/// What each of the `UNITS` is called in English.
+const UNIT_NAMES: [&str; 4] = ["day", "hour", "minute", "second"];
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    /// Parses a duration made up of one or more numbers, each
+    /// followed by a unit of `d`, `h`, `m` or `s`. The units have
+    /// to go from largest to smallest, and each can only show up
+    /// once, like `90m`, `1h30m` or `1d12h`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidInterval {
+            token: s.to_owned(),
+            span: 0..s.len(),
+        };
+        let mut units = UNITS.iter();
+        let mut total: i64 = 0;
+        let mut rest = s;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let (number, tail) = rest.split_at(digits);
+            let number = number.parse::<i64>().map_err(|_| invalid())?;
+
+            let mut chars = tail.chars();
+            let unit = chars.next().ok_or_else(invalid)?;
+            let (_, seconds) = units.find(|(name, _)| *name == unit).ok_or_else(invalid)?;
+
+            total = number
+                .checked_mul(*seconds)
+                .and_then(|seconds| total.checked_add(seconds))
+                .ok_or_else(invalid)?;
+            rest = chars.as_str();
+        }
+
+        if total <= 0 {
+            return Err(invalid());
+        }
+        Duration::from_std(std::time::Duration::from_secs(total as u64))
+            .map(|every| Interval { every })
+            .map_err(|_| invalid())
+    }
+}
+
+/// Writes the interval out in the largest units that
+/// fit, so `90m` comes back out as `1h30m`.
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.every.num_seconds();
+        for (name, seconds) in UNITS {
+            if rest >= seconds {
+                write!(f, "{}{name}", rest / seconds)?;
+                rest %= seconds;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
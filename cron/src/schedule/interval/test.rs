@@ -0,0 +1,59 @@
+use super::Interval;
+use crate::Error;
+use chrono::{Duration, TimeZone, Utc};
+
+fn every(s: &str) -> Duration {
+    s.parse::<Interval>().unwrap().every
+}
+
+#[test]
+fn single_units() {
+    assert_eq!(Duration::seconds(45), every("45s"));
+    assert_eq!(Duration::minutes(90), every("90m"));
+    assert_eq!(Duration::hours(2), every("2h"));
+    assert_eq!(Duration::days(1), every("1d"));
+}
+
+#[test]
+fn combined_units() {
+    assert_eq!(Duration::minutes(90), every("1h30m"));
+    assert_eq!(Duration::hours(36), every("1d12h"));
+    assert_eq!(Duration::seconds(3_661), every("1h1m1s"));
+}
+
+#[test]
+fn malformed_intervals() {
+    for interval in [
+        "",
+        "90",
+        "m",
+        "1x",
+        "30m1h",
+        "1h1h",
+        "1.5h",
+        "-5m",
+        "0s",
+        "0h0m",
+        "1h 30m",
+        "99999999999999999999s",
+    ] {
+        assert!(
+            matches!(
+                interval.parse::<Interval>(),
+                Err(Error::InvalidInterval { .. })
+            ),
+            "{interval:?} should not parse"
+        );
+    }
+}
+
+#[test]
+fn after_adds_the_interval() {
+    let interval: Interval = "1h30m".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 5, 23, 0, 0).unwrap();
+
+    assert_eq!(
+        Some(Utc.with_ymd_and_hms(2023, 6, 6, 0, 30, 0).unwrap()),
+        interval.after(&start)
+    );
+}
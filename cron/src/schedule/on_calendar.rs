@@ -0,0 +1,278 @@
+//! Parses systemd's `OnCalendar=` syntax, like
+//! `Mon..Fri *-*-* 13..18:16:00`, into the same `FieldTable`
+//! that cron expressions turn into.
+//!
+//! An expression is a list of weekdays, a date written as
+//! `year-month-day` or `month-day`, a time written as
+//! `hour:minute:second` or `hour:minute`, and a timezone, in
+//! that order. Every one of them can be left out. A missing
+//! date is `*-*-*`, and a missing time is midnight.
+//!
+//! Each part of the date and time is `*`, a value, or a range
+//! `a..b`, and any of those can be followed by a repetition
+//! `/n`. Lists of them are separated with commas. A day after
+//! a `~` rather than a `-` counts back from the end of the
+//! month, so `*-02~01` is the last day of February.
+
+use super::{
+    fields::{Builder, FieldTable, MonthDayModifier},
+    parser,
+};
+use crate::{Error, Field};
+use chrono::Weekday;
+use std::collections::BTreeSet;
+
+/// The names that systemd expands into whole expressions.
+const SHORTHANDS: [(&str, &str); 9] = [
+    ("minutely", "*-*-* *:*:00"),
+    ("hourly", "*-*-* *:00:00"),
+    ("daily", "*-*-* 00:00:00"),
+    ("weekly", "Mon *-*-* 00:00:00"),
+    ("monthly", "*-*-01 00:00:00"),
+    ("quarterly", "*-01,04,07,10-01 00:00:00"),
+    ("semiannually", "*-01,07-01 00:00:00"),
+    ("yearly", "*-01-01 00:00:00"),
+    ("annually", "*-01-01 00:00:00"),
+];
+
+/// The name of a timezone, and where it starts.
+type Timezone<'a> = (usize, &'a str);
+
+/// The days of the month from one part of a date.
+enum Days {
+    /// `*`, every day.
+    Every,
+    /// Days counted from the start of the month.
+    Plain(Vec<u8>),
+    /// Days after a `~`, counted back from the end of the month.
+    FromEnd(Vec<MonthDayModifier>),
+}
+
+/// Parses an `OnCalendar=` expression into a `FieldTable`,
+/// along with the timezone at the end of it, if there is one.
+/// The timezone is left for the caller to look up.
+///
+/// systemd runs a timer on the days that match both the
+/// weekdays and the date, while a `FieldTable` runs on
+/// the days that match either, so weekdays can't be
+/// combined with particular days of the month.
+pub(super) fn parse(expression: &str) -> Result<(FieldTable, Option<Timezone<'_>>), Error> {
+    let mut parts = parser::split(expression);
+    let Some(&(_, first)) = parts.first() else {
+        return Err(Error::Empty);
+    };
+    if let Some(&(_, expanded)) = SHORTHANDS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(first))
+    {
+        // The expansions always parse, so their
+        // offsets never end up in an error.
+        parts.splice(..1, parser::split(expanded));
+    }
+
+    let mut parts = parts.into_iter().peekable();
+    let weekdays = parts
+        .next_if(|&(_, part)| is_weekdays(part))
+        .map(|(at, part)| {
+            weekdays(part)
+                .map(|days| (at, days))
+                .map_err(|error| error.shifted(at))
+        })
+        .transpose()?;
+    let date = parts.next_if(|&(_, part)| !part.contains(':') && part.contains(['-', '~']));
+    let time = parts.next_if(|&(_, part)| part.contains(':'));
+    // Anything left over goes into the timezone, so
+    // that it gets reported as part of the name.
+    let timezone = parts.next();
+    let timezone = timezone.map(|(at, part)| match parts.last() {
+        Some((last_at, last)) => (at, &expression[at..last_at + last.len()]),
+        None => (at, part),
+    });
+
+    let mut builder = FieldTable::builder();
+    let days = match date {
+        Some((at, date)) => self::date(date, &mut builder).map_err(|error| error.shifted(at))?,
+        None => {
+            builder.with_months_iter(1..=12);
+            Days::Every
+        }
+    };
+    match time {
+        Some((at, time)) => self::time(time, &mut builder).map_err(|error| error.shifted(at))?,
+        None => {
+            builder.with_secs(0).with_mins(0).with_hrs(0);
+        }
+    }
+    match (weekdays, days) {
+        (None, Days::Every) => builder.with_days_of_the_month_only_iter(1..=31),
+        (None, Days::Plain(days)) => builder.with_days_of_the_month_only_iter(days),
+        (None, Days::FromEnd(modifiers)) => builder
+            .with_days_of_the_month_only_iter([])
+            .with_days_of_the_month_modifiers(modifiers),
+        (Some((_, weekdays)), Days::Every) => builder.with_days_of_the_week_only_iter(weekdays),
+        (Some((at, _)), _) => {
+            let (date_at, date) = date.unwrap_or_default();
+            return Err(Error::Unsupported {
+                token: expression[at..date_at + date.len()].to_owned(),
+                span: at..date_at + date.len(),
+            });
+        }
+    };
+    Ok((builder.build()?, timezone))
+}
+
+/// Returns true if `part` starts with the name of a weekday.
+fn is_weekdays(part: &str) -> bool {
+    let name = part.split([',', '.', '-']).next().unwrap_or_default();
+    name.parse::<Weekday>().is_ok()
+}
+
+/// Parses a list of weekdays like `Mon..Fri,Sun`, numbered the way
+/// cron numbers them. Names can be written out in full, and ranges
+/// can be written with either `..` or `-`.
+///
+/// systemd's weeks start on Monday, so `Sat..Sun` is the weekend,
+/// and a range that goes from Sunday to Monday goes backwards.
+fn weekdays(part: &str) -> Result<Vec<u8>, Error> {
+    let field = Field::DaysOfWeek;
+    let weekday = |name: &str| name.parse::<Weekday>().map_err(|_| field.invalid(name));
+    let mut days = BTreeSet::new();
+    let mut at = 0;
+    for item in part.split(',') {
+        let (start, end, end_at) = match item.split_once("..") {
+            Some((start, end)) => (start, end, start.len() + 2),
+            None => match item.split_once('-') {
+                Some((start, end)) => (start, end, start.len() + 1),
+                None => (item, item, 0),
+            },
+        };
+        let first = weekday(start).map_err(|error| error.shifted(at))?;
+        let last = weekday(end).map_err(|error| error.shifted(at + end_at))?;
+        let (first, last) = (first.num_days_from_monday(), last.num_days_from_monday());
+        if first > last {
+            return Err(field.invalid(item).shifted(at));
+        }
+        days.extend((first..=last).map(|day| ((day + 1) % 7) as u8));
+        at += item.len() + 1;
+    }
+    Ok(days.into_iter().collect())
+}
+
+/// Parses a date like `*-*-1,15`, `2023-06-01`, or `*-02~03`, handing
+/// the years, if they're restricted, and the months to `builder`. The
+/// days get returned, since which day method to use depends on
+/// the weekdays too.
+fn date(date: &str, builder: &mut Builder) -> Result<Days, Error> {
+    let (front, days, from_end) = match date.split_once('~') {
+        Some((front, days)) => (front, days, true),
+        None => match date.rsplit_once('-') {
+            Some((front, days)) => (front, days, false),
+            None => return Err(Field::DaysOfMonth.invalid(date)),
+        },
+    };
+    let days_at = front.len() + 1;
+    let (years, months, months_at) = match front.split_once('-') {
+        Some((years, months)) => (Some(years), months, years.len() + 1),
+        None => (None, front, 0),
+    };
+
+    match years {
+        None | Some("*") => {}
+        Some(years) => {
+            builder.with_years_iter(values(Field::Years, years)?);
+        }
+    }
+    let months = values(Field::Months, months).map_err(|error| error.shifted(months_at))?;
+    builder.with_months_iter(narrow(months));
+    let days = match (days, from_end) {
+        ("*", false) => Days::Every,
+        (days, from_end) => {
+            let days = values(Field::DaysOfMonth, days).map_err(|error| error.shifted(days_at))?;
+            match from_end {
+                true => Days::FromEnd(
+                    days.into_iter()
+                        .map(|day| MonthDayModifier::Last {
+                            offset: day as u8 - 1,
+                        })
+                        .collect(),
+                ),
+                false => Days::Plain(narrow(days)),
+            }
+        }
+    };
+    Ok(days)
+}
+
+/// Parses a time like `13..18:16:00` or `*:0/15`, handing the hours,
+/// minutes and seconds to `builder`. Without any seconds, it runs
+/// on the minute.
+fn time(time: &str, builder: &mut Builder) -> Result<(), Error> {
+    let parts: Vec<&str> = time.split(':').collect();
+    let (hours, minutes, seconds) = match parts[..] {
+        [hours, minutes] => (hours, minutes, "0"),
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        _ => return Err(Field::Hours.invalid(time)),
+    };
+    let minutes_at = hours.len() + 1;
+    let seconds_at = minutes_at + minutes.len() + 1;
+    builder
+        .with_hrs_iter(narrow(values(Field::Hours, hours)?))
+        .with_mins_iter(narrow(
+            values(Field::Minutes, minutes).map_err(|error| error.shifted(minutes_at))?,
+        ))
+        .with_secs_iter(narrow(
+            values(Field::Seconds, seconds).map_err(|error| error.shifted(seconds_at))?,
+        ));
+    Ok(())
+}
+
+/// Parses a comma-separated list of items, returning
+/// the sorted values that they stand for.
+fn values(field: Field, list: &str) -> Result<Vec<u32>, Error> {
+    let mut values = BTreeSet::new();
+    let mut at = 0;
+    for item in list.split(',') {
+        values.extend(self::item(field, item).map_err(|error| error.shifted(at))?);
+        at += item.len() + 1;
+    }
+    Ok(values.into_iter().collect())
+}
+
+/// Parses one item, which is `*`, a value, or a range `a..b`,
+/// optionally followed by a repetition `/n`. A single value
+/// with a repetition keeps going up to the highest value
+/// of the field, so `0/15` is the same as `0,15,30,45`.
+fn item(field: Field, item: &str) -> Result<impl Iterator<Item = u32>, Error> {
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => match step.parse::<u32>() {
+            Ok(step) if step > 0 => (range, Some(step)),
+            _ => return Err(field.invalid(step).shifted(range.len() + 1)),
+        },
+        None => (item, None),
+    };
+    let (start, end) = if range == "*" {
+        (field.min(), field.max())
+    } else if let Some((start, end)) = range.split_once("..") {
+        let end_at = start.len() + 2;
+        (
+            field.value(start)?,
+            field.value(end).map_err(|error| error.shifted(end_at))?,
+        )
+    } else {
+        let start = field.value(range)?;
+        (start, step.map_or(start, |_| field.max()))
+    };
+    if start > end {
+        return Err(field.invalid(range));
+    }
+    Ok((start..=end).step_by(step.unwrap_or(1) as usize))
+}
+
+/// Every field besides the years fits inside of a
+/// `u8`, once it's been checked to be in range.
+fn narrow(values: Vec<u32>) -> Vec<u8> {
+    values.into_iter().map(|value| value as u8).collect()
+}
+
+#[cfg(test)]
+mod test;
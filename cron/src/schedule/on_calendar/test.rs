@@ -0,0 +1,136 @@
+use crate::{schedule::Schedule, Error, Field};
+
+/// Parses an `OnCalendar=` expression, and writes it back out
+/// as the cron expression it turned into.
+fn cron(expression: &str) -> String {
+    Schedule::from_on_calendar(expression)
+        .unwrap_or_else(|error| panic!("{expression:?}: {error}"))
+        .to_string()
+}
+
+#[test]
+fn parses_weekdays_dates_and_times() {
+    assert_eq!(
+        "0 16 13-18 * * Mon-Fri",
+        cron("Mon..Fri *-*-* 13..18:16:00")
+    );
+    assert_eq!("0 16 13-18 * * Mon-Fri", cron("Mon-Fri 13..18:16"));
+    assert_eq!("0 0 9 * * Sun,Sat", cron("Sat,Sunday 09:00"));
+    assert_eq!("0 0 0 * * Mon,Wed-Fri", cron("mon,Wed..fri"));
+    assert_eq!("30 15 12 1 Jun * 2023", cron("2023-06-01 12:15:30"));
+    assert_eq!("0 0 0 1,15 Jan,Jul *", cron("01,07-1,15"));
+}
+
+#[test]
+fn parts_can_repeat() {
+    assert_eq!("0 */15 * * * *", cron("*:0/15"));
+    assert_eq!("0 */15 * * * *", cron("*:*/15"));
+    assert_eq!("0 0 8-16/2 * * *", cron("08..16/2:00"));
+    assert_eq!("0 0 0 1 */3 *", cron("*-1/3-1"));
+}
+
+#[test]
+fn missing_parts_have_defaults() {
+    assert_eq!("0 0 0 * * *", cron("*-*-*"));
+    assert_eq!("0 0 0 * * Fri", cron("Fri"));
+    assert_eq!("0 30 6 * * *", cron("6:30"));
+}
+
+#[test]
+fn tilde_counts_from_the_end_of_the_month() {
+    assert_eq!("0 0 0 L Feb *", cron("*-02~01"));
+    assert_eq!("0 0 12 L,L-2 * *", cron("*-*~1,3 12:00"));
+}
+
+#[test]
+fn shorthands_expand() {
+    for (shorthand, expected) in [
+        ("minutely", "0 * * * * *"),
+        ("hourly", "0 0 * * * *"),
+        ("daily", "0 0 0 * * *"),
+        ("weekly", "0 0 0 * * Mon"),
+        ("monthly", "0 0 0 1 * *"),
+        ("quarterly", "0 0 0 1 */3 *"),
+        ("semiannually", "0 0 0 1 Jan,Jul *"),
+        ("yearly", "0 0 0 1 Jan *"),
+        ("annually", "0 0 0 1 Jan *"),
+    ] {
+        assert_eq!(expected, cron(shorthand), "{shorthand}");
+    }
+}
+
+#[test]
+fn weekdays_with_days_of_the_month_are_unsupported() {
+    let error = Schedule::from_on_calendar("Fri *-*-13 00:00").unwrap_err();
+    assert_eq!(
+        Error::Unsupported {
+            token: "Fri *-*-13".into(),
+            span: 0..10,
+        },
+        error
+    );
+    assert_eq!(
+        "`Fri *-*-13` at column 1 isn't supported",
+        error.to_string()
+    );
+
+    // Restricting the months and years along with them is fine.
+    assert_eq!("0 0 0 * Dec Fri 2024", cron("Fri 2024-12-*"));
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    for (expression, field, span) in [
+        ("Mon..Fri 13..25:16", Field::Hours, 13..15),
+        ("*-13-01", Field::Months, 2..4),
+        ("*-*-32", Field::DaysOfMonth, 4..6),
+        ("Mon *-*-* 12:00:60", Field::Seconds, 16..18),
+    ] {
+        match Schedule::from_on_calendar(expression) {
+            Err(Error::OutOfRange {
+                field: found,
+                span: found_span,
+                ..
+            }) => {
+                assert_eq!((field, span), (found, found_span), "{expression:?}");
+            }
+            other => panic!("{expression:?}: {other:?}"),
+        }
+    }
+
+    assert!(matches!(
+        Schedule::from_on_calendar("Fri..Mon"),
+        Err(Error::InvalidField {
+            field: Field::DaysOfWeek,
+            ..
+        })
+    ));
+    assert!(matches!(
+        Schedule::from_on_calendar("*-*-* 12"),
+        Err(Error::InvalidTimezone { .. })
+    ));
+    assert!(matches!(
+        Schedule::from_on_calendar("12:00:00:00"),
+        Err(Error::InvalidField {
+            field: Field::Hours,
+            ..
+        })
+    ));
+    assert_eq!(
+        Err(Error::Empty),
+        Schedule::from_on_calendar(" ").map(|_| ())
+    );
+}
+
+#[test]
+fn timezones_come_last() {
+    let error = Schedule::from_on_calendar("*-*-* 09:00 Mars/Olympus_Mons").unwrap_err();
+    assert_eq!(Some(12..29), error.span());
+
+    #[cfg(feature = "chrono-tz")]
+    {
+        let schedule = Schedule::from_on_calendar("Mon 09:00 Europe/Berlin").unwrap();
+        assert_eq!(Some(chrono_tz::Europe::Berlin), schedule.timezone());
+        assert_eq!("CRON_TZ=Europe/Berlin 0 0 9 * * Mon", schedule.to_string());
+    }
+}
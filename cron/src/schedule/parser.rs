@@ -0,0 +1,515 @@
+use super::fields::{FieldTable, MonthDayModifier, WeekdayModifier, FIRST_YEAR, LAST_YEAR};
+use crate::{Error, Field};
+use std::collections::BTreeSet;
+
+/// The names of the months and days of the week, spelled the
+/// way they get written back out. Parsing ignores their case.
+pub(super) const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+pub(super) const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+impl Field {
+    /// The lowest value allowed for this field.
+    pub(super) fn min(self) -> u32 {
+        match self {
+            Field::Seconds | Field::Minutes | Field::Hours | Field::DaysOfWeek => 0,
+            Field::DaysOfMonth | Field::Months => 1,
+            Field::Years => FIRST_YEAR,
+        }
+    }
+
+    /// The highest value allowed for this field.
+    /// For the days of the week, both 0 and 7
+    /// are accepted as Sunday.
+    pub(super) fn max(self) -> u32 {
+        match self {
+            Field::Seconds | Field::Minutes => 59,
+            Field::Hours => 23,
+            Field::DaysOfMonth => 31,
+            Field::Months => 12,
+            Field::DaysOfWeek => 7,
+            Field::Years => LAST_YEAR,
+        }
+    }
+
+    /// An error for a `token` that can't be parsed,
+    /// spanning the whole token.
+    pub(super) fn invalid(self, token: &str) -> Error {
+        Error::InvalidField {
+            field: self,
+            token: token.to_owned(),
+            span: 0..token.len(),
+        }
+    }
+
+    /// An error for a `token` that parsed to a value
+    /// outside of `min..=max`, spanning the whole token.
+    fn out_of_range(self, token: &str, min: u32, max: u32) -> Error {
+        Error::OutOfRange {
+            field: self,
+            token: token.to_owned(),
+            span: 0..token.len(),
+            min,
+            max,
+        }
+    }
+
+    /// Parses a single value, which is either a number
+    /// or, for the months and days of the week, a
+    /// three-letter name like `Jan` or `Mon`.
+    pub(super) fn value(self, s: &str) -> Result<u32, Error> {
+        let is_number = !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        let value = if let Ok(value) = s.parse::<u32>() {
+            value
+        } else if is_number {
+            // Too big to even fit in a `u32`.
+            return Err(self.out_of_range(s, self.min(), self.max()));
+        } else {
+            let (names, offset): (&[&str], u32) = match self {
+                Field::Months => (&MONTHS, 1),
+                Field::DaysOfWeek => (&WEEKDAYS, 0),
+                _ => return Err(self.invalid(s)),
+            };
+            let position = names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(s))
+                .ok_or_else(|| self.invalid(s))?;
+            position as u32 + offset
+        };
+        if value < self.min() || value > self.max() {
+            Err(self.out_of_range(s, self.min(), self.max()))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Parses one comma-separated item of a field, inserting
+    /// every value it stands for into `values`.
+    ///
+    /// An item is either `*`, a single value, or a range `a-b`,
+    /// optionally followed by a step `/n`. A single value
+    /// with a step, like `5/15`, runs from that value up to
+    /// the highest value of the field. The day fields also
+    /// take `?`, which means the same thing as `*`.
+    ///
+    /// In place of the range, `H` or `H(a-b)` picks a value by
+    /// hashing `key`. See `hashed` for what those stand for.
+    fn item(self, item: &str, key: Option<&str>, values: &mut BTreeSet<u32>) -> Result<(), Error> {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(self.invalid(step).shifted(range.len() + 1)),
+            },
+            None => (item, None),
+        };
+        if range.starts_with('H') {
+            // A hashed year would as likely as not be one that has
+            // already gone by, leaving a schedule that never runs.
+            if self == Field::Years {
+                return Err(self.invalid(range));
+            }
+            values.extend(self.hashed(range, step, key)?);
+            return Ok(());
+        }
+
+        let is_day = matches!(self, Field::DaysOfMonth | Field::DaysOfWeek);
+        let (start, end) = if range == "*" || (is_day && range == "?") {
+            (self.min(), self.max())
+        } else if let Some((start, end)) = range.split_once('-') {
+            let end_at = start.len() + 1;
+            (
+                self.value(start)?,
+                self.value(end).map_err(|error| error.shifted(end_at))?,
+            )
+        } else {
+            let start = self.value(range)?;
+            (start, step.map_or(start, |_| self.max()))
+        };
+        if start > end {
+            return Err(self.invalid(range));
+        }
+
+        values.extend((start..=end).step_by(step.unwrap_or(1) as usize));
+        Ok(())
+    }
+
+    /// The values that a bare `H` picks from. The days of the
+    /// month stop at the 28th so that every month has them.
+    fn hash_range(self) -> (u32, u32) {
+        match self {
+            Field::DaysOfMonth => (1, 28),
+            Field::DaysOfWeek => (0, 6),
+            _ => (self.min(), self.max()),
+        }
+    }
+
+    /// Turns a hashed item into the values it stands for, the
+    /// way Jenkins does. `H` is a single value somewhere in the
+    /// field, and `H(a-b)` is one from `a` through `b`. With a
+    /// step, like `H/15`, the hash picks where the step starts
+    /// instead, out of the first `15` values of the range.
+    ///
+    /// The same key always picks the same values, so a job keeps
+    /// its time across restarts, while different keys spread
+    /// their jobs out over the field.
+    fn hashed(
+        self,
+        range: &str,
+        step: Option<u32>,
+        key: Option<&str>,
+    ) -> Result<impl Iterator<Item = u32>, Error> {
+        let (start, end) = match &range[1..] {
+            "" => self.hash_range(),
+            bounds => {
+                let (start, end) = bounds
+                    .strip_prefix('(')
+                    .and_then(|bounds| bounds.strip_suffix(')'))
+                    .and_then(|bounds| bounds.split_once('-'))
+                    .ok_or_else(|| self.invalid(range))?;
+                let end_at = "H(".len() + start.len() + 1;
+                let start = self.value(start).map_err(|error| error.shifted(2))?;
+                let end = self.value(end).map_err(|error| error.shifted(end_at))?;
+                match start <= end {
+                    true => (start, end),
+                    false => return Err(self.invalid(range)),
+                }
+            }
+        };
+        let key = key.ok_or_else(|| Error::MissingHashKey {
+            token: range.to_owned(),
+            span: 0..range.len(),
+        })?;
+
+        let hash = hash(key, self);
+        let (first, step) = match step {
+            Some(step) => {
+                let starts = step.min(end - start + 1);
+                (start + (hash % starts as u64) as u32, step)
+            }
+            None => {
+                let first = start + (hash % (end - start + 1) as u64) as u32;
+                (first, end - first + 1)
+            }
+        };
+        Ok((first..=end).step_by(step as usize))
+    }
+
+    /// Parses an entire field, returning the sorted
+    /// and de-duplicated values that it allows.
+    fn values(self, field: &str, key: Option<&str>) -> Result<BTreeSet<u32>, Error> {
+        let (values, _) = self.values_with(field, key, |_| Ok(None::<()>))?;
+        Ok(values)
+    }
+
+    /// Parses an entire field just like `values`, except that
+    /// each item is first handed to `modifier`. Any item that
+    /// turns into a modifier gets collected separately.
+    fn values_with<M>(
+        self,
+        field: &str,
+        key: Option<&str>,
+        mut modifier: impl FnMut(&str) -> Result<Option<M>, Error>,
+    ) -> Result<(BTreeSet<u32>, Vec<M>), Error> {
+        let mut values = BTreeSet::new();
+        let mut modifiers = Vec::new();
+        let mut at = 0;
+        for item in field.split(',') {
+            let parsed = if item.is_empty() {
+                Err(self.invalid(item))
+            } else {
+                modifier(item).and_then(|parsed| match parsed {
+                    Some(modifier) => {
+                        modifiers.push(modifier);
+                        Ok(())
+                    }
+                    None => self.item(item, key, &mut values),
+                })
+            };
+            parsed.map_err(|error| error.shifted(at))?;
+            at += item.len() + 1;
+        }
+        if self == Field::DaysOfWeek && values.remove(&7) {
+            values.insert(0);
+        }
+        Ok((values, modifiers))
+    }
+
+    /// Parses any field besides the years, which
+    /// don't fit inside of a `u8`. Any `H` in the
+    /// field gets its value from hashing `key`.
+    pub fn parse(self, field: &str, key: Option<&str>) -> Result<Vec<u8>, Error> {
+        Ok(self
+            .values(field, key)?
+            .into_iter()
+            .map(|value| value as u8)
+            .collect())
+    }
+
+    /// Parses the year field.
+    pub fn parse_years(field: &str, key: Option<&str>) -> Result<Vec<u32>, Error> {
+        Ok(Field::Years.values(field, key)?.into_iter().collect())
+    }
+
+    /// Parses the days of the month, which on top of
+    /// the usual items can hold `L`, `L-n`, `LW` and `nW`.
+    pub fn parse_days_of_month(
+        field: &str,
+        key: Option<&str>,
+    ) -> Result<(Vec<u8>, Vec<MonthDayModifier>), Error> {
+        let (values, modifiers) = Field::DaysOfMonth.values_with(field, key, month_day_modifier)?;
+        Ok((values.into_iter().map(|day| day as u8).collect(), modifiers))
+    }
+
+    /// Parses the days of the week, which on top of
+    /// the usual items can hold `nL` and `n#k`.
+    pub fn parse_days_of_week(
+        field: &str,
+        key: Option<&str>,
+    ) -> Result<(Vec<u8>, Vec<WeekdayModifier>), Error> {
+        let (values, modifiers) = Field::DaysOfWeek.values_with(field, key, weekday_modifier)?;
+        Ok((values.into_iter().map(|day| day as u8).collect(), modifiers))
+    }
+}
+
+/// Turns `L`, `L-n`, `LW` and `nW` into modifiers,
+/// leaving every other item alone.
+fn month_day_modifier(item: &str) -> Result<Option<MonthDayModifier>, Error> {
+    let field = Field::DaysOfMonth;
+    let modifier = if item.eq_ignore_ascii_case("L") {
+        MonthDayModifier::Last { offset: 0 }
+    } else if item.eq_ignore_ascii_case("LW") {
+        MonthDayModifier::LastWeekday
+    } else if item
+        .get(..2)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("L-"))
+    {
+        let offset = &item[2..];
+        let max = field.max() - 1;
+        match offset.parse::<u32>() {
+            Ok(value) if value <= max => MonthDayModifier::Last {
+                offset: value as u8,
+            },
+            Ok(_) => return Err(field.out_of_range(offset, 0, max).shifted(2)),
+            Err(_) => return Err(field.invalid(offset).shifted(2)),
+        }
+    } else if let Some(day) = item.strip_suffix(['W', 'w']) {
+        MonthDayModifier::NearestWeekday(field.value(day)? as u8)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(modifier))
+}
+
+/// Turns `nL` and `n#k` into modifiers,
+/// leaving every other item alone.
+fn weekday_modifier(item: &str) -> Result<Option<WeekdayModifier>, Error> {
+    let field = Field::DaysOfWeek;
+    let weekday = |weekday: &str| -> Result<u8, Error> {
+        let days_in_a_week = 7;
+        Ok((field.value(weekday)? % days_in_a_week) as u8)
+    };
+    let modifier = if let Some((day, nth)) = item.split_once('#') {
+        let nth_at = day.len() + 1;
+        let nth = match nth.parse::<u8>() {
+            Ok(value) if (1..=5).contains(&value) => value,
+            Ok(_) => return Err(field.out_of_range(nth, 1, 5).shifted(nth_at)),
+            Err(_) => return Err(field.invalid(nth).shifted(nth_at)),
+        };
+        WeekdayModifier::Nth {
+            weekday: weekday(day)?,
+            nth,
+        }
+    } else if let Some(day) = item.strip_suffix(['L', 'l']).filter(|day| !day.is_empty()) {
+        WeekdayModifier::Last(weekday(day)?)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(modifier))
+}
+
+/// Hashes `key` along with the field, so that each field of
+/// the same job lands somewhere different.
+fn hash(key: &str, field: Field) -> u64 {
+    stable_hash(key.bytes().chain([field as u8]))
+}
+
+/// Hashes `bytes` with 64-bit FNV-1a, which unlike the standard
+/// library's hashers is guaranteed to come out the same on every
+/// platform and release, so it's safe to store what it gives.
+///
+/// FNV leaves its low bits poorly mixed when keys only differ
+/// at the end, like `job-1` and `job-2`, and those are the bits
+/// that taking the remainder looks at. Finishing off with the
+/// SplitMix64 mixer stirs the high bits back down into them.
+pub(super) fn stable_hash(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let hash = bytes.into_iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    });
+
+    let hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// Returns true if the field places no
+/// restriction on its values, which decides
+/// how the two day fields get combined. Only
+/// a bare `*` or `?` does, so a step like `*/2`
+/// still picks its days.
+fn is_wildcard(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+/// Splits an expression into its whitespace-separated
+/// fields, each along with the byte offset it starts at.
+pub(super) fn split(expression: &str) -> Vec<(usize, &str)> {
+    expression
+        .split_whitespace()
+        // Every field is a slice of the expression, so the
+        // distance between the two is the field's offset.
+        .map(|field| {
+            (
+                field.as_ptr() as usize - expression.as_ptr() as usize,
+                field,
+            )
+        })
+        .collect()
+}
+
+/// What an expression starts with to say
+/// which timezone it runs in.
+const TIMEZONE_PREFIX: &str = "CRON_TZ=";
+
+/// Takes a `CRON_TZ=America/Chicago` prefix off of the
+/// front of the fields, and looks up the timezone it names.
+#[cfg(feature = "chrono-tz")]
+pub(super) fn timezone(fields: &mut Vec<(usize, &str)>) -> Result<Option<chrono_tz::Tz>, Error> {
+    let Some(&(at, field)) = fields.first() else {
+        return Ok(None);
+    };
+    let Some(name) = field.strip_prefix(TIMEZONE_PREFIX) else {
+        return Ok(None);
+    };
+    let timezone = name.parse().map_err(|_| Error::InvalidTimezone {
+        token: name.to_owned(),
+        span: at + TIMEZONE_PREFIX.len()..at + field.len(),
+    })?;
+    fields.remove(0);
+    Ok(Some(timezone))
+}
+
+/// Rejects a `CRON_TZ=` prefix, since without the
+/// `chrono-tz` feature there's no timezone to look up.
+#[cfg(not(feature = "chrono-tz"))]
+pub(super) fn timezone(fields: &mut Vec<(usize, &str)>) -> Result<(), Error> {
+    match fields.first() {
+        Some(&(at, field)) if field.starts_with(TIMEZONE_PREFIX) => Err(Error::InvalidTimezone {
+            token: field[TIMEZONE_PREFIX.len()..].to_owned(),
+            span: at + TIMEZONE_PREFIX.len()..at + field.len(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Builds a `FieldTable` out of the six fields of
+/// an expression, in the order seconds, minutes,
+/// hours, days of the month, months, days of the week.
+///
+/// If only one of the day fields is restricted, then
+/// only that one is used. If both are restricted, then
+/// a day matches when either of them matches, just like
+/// in the classic crontab.
+pub(super) fn six_fields(fields: &[(usize, &str)], key: Option<&str>) -> Result<FieldTable, Error> {
+    let &[secs, mins, hrs, days_of_month, months, days_of_week] = fields else {
+        return Err(Error::WrongNumberOfFields {
+            found: fields.len(),
+        });
+    };
+    table(
+        [secs, mins, hrs, days_of_month, months, days_of_week],
+        None,
+        key,
+    )
+}
+
+/// Builds a `FieldTable` out of the six fields of an
+/// expression, followed by the years that the schedule
+/// is allowed to run in. The schedule stops once
+/// the last of the years is over.
+pub(super) fn seven_fields(
+    fields: &[(usize, &str)],
+    key: Option<&str>,
+) -> Result<FieldTable, Error> {
+    let &[secs, mins, hrs, days_of_month, months, days_of_week, years] = fields else {
+        return Err(Error::WrongNumberOfFields {
+            found: fields.len(),
+        });
+    };
+    table(
+        [secs, mins, hrs, days_of_month, months, days_of_week],
+        Some(years),
+        key,
+    )
+}
+
+fn table(
+    fields: [(usize, &str); 6],
+    years: Option<(usize, &str)>,
+    key: Option<&str>,
+) -> Result<FieldTable, Error> {
+    let [secs, mins, hrs, days_of_month, months, days_of_week] = fields;
+    // Each field reports errors relative to its own start.
+    let at = |(at, _): (usize, &str)| move |error: Error| error.shifted(at);
+
+    let mut builder = FieldTable::builder();
+    builder
+        .with_secs_iter(Field::Seconds.parse(secs.1, key).map_err(at(secs))?)
+        .with_mins_iter(Field::Minutes.parse(mins.1, key).map_err(at(mins))?)
+        .with_hrs_iter(Field::Hours.parse(hrs.1, key).map_err(at(hrs))?)
+        .with_months_iter(Field::Months.parse(months.1, key).map_err(at(months))?);
+
+    let (month, month_modifiers) =
+        Field::parse_days_of_month(days_of_month.1, key).map_err(at(days_of_month))?;
+    let (week, week_modifiers) =
+        Field::parse_days_of_week(days_of_week.1, key).map_err(at(days_of_week))?;
+    match (is_wildcard(days_of_month.1), is_wildcard(days_of_week.1)) {
+        (_, true) => builder.with_days_of_the_month_only_iter(month),
+        (true, false) => builder.with_days_of_the_week_only_iter(week),
+        (false, false) => builder.with_days_of_both_iter(week, month),
+    };
+    builder
+        .with_days_of_the_month_modifiers(month_modifiers)
+        .with_days_of_the_week_modifiers(week_modifiers);
+
+    if let Some(years) = years.filter(|(_, years)| *years != "*") {
+        builder.with_years_iter(Field::parse_years(years.1, key).map_err(at(years))?);
+    }
+
+    builder.build().map_err(Error::from)
+}
+
+/// Builds a `FieldTable` out of a classic five-field
+/// crontab expression, which is the same as the
+/// six-field version without the seconds. Every
+/// occurrence lands on the start of the minute.
+pub(super) fn five_fields(
+    fields: &[(usize, &str)],
+    key: Option<&str>,
+) -> Result<FieldTable, Error> {
+    let &[mins, hrs, days_of_month, months, days_of_week] = fields else {
+        return Err(Error::WrongNumberOfFields {
+            found: fields.len(),
+        });
+    };
+    six_fields(
+        &[(0, "0"), mins, hrs, days_of_month, months, days_of_week],
+        key,
+    )
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,550 @@
+use super::{five_fields, seven_fields, six_fields, split};
+use crate::{schedule::Schedule, Error, Field};
+use std::collections::BTreeSet;
+
+#[test]
+fn wildcard_covers_whole_range() {
+    assert_eq!(
+        (0..60).collect::<Vec<u8>>(),
+        Field::Seconds.parse("*", None).unwrap()
+    );
+    assert_eq!(
+        (0..24).collect::<Vec<u8>>(),
+        Field::Hours.parse("*", None).unwrap()
+    );
+    assert_eq!(
+        (1..=31).collect::<Vec<u8>>(),
+        Field::DaysOfMonth.parse("*", None).unwrap()
+    );
+    assert_eq!(
+        (1..=12).collect::<Vec<u8>>(),
+        Field::Months.parse("*", None).unwrap()
+    );
+    assert_eq!(
+        (0..7).collect::<Vec<u8>>(),
+        Field::DaysOfWeek.parse("*", None).unwrap()
+    );
+}
+
+#[test]
+fn lists_are_sorted_and_deduplicated() {
+    let hours = Field::Hours.parse("18,13,14,13,15", None).unwrap();
+    assert_eq!(vec![13, 14, 15, 18], hours);
+}
+
+#[test]
+fn ranges_and_steps() {
+    assert_eq!(
+        vec![0, 15, 30, 45],
+        Field::Minutes.parse("*/15", None).unwrap()
+    );
+    assert_eq!(
+        vec![13, 14, 15, 16, 17, 18],
+        Field::Hours.parse("13-18", None).unwrap()
+    );
+    assert_eq!(
+        vec![1, 4, 7, 10],
+        Field::Months.parse("1-12/3", None).unwrap()
+    );
+    assert_eq!(vec![5, 25, 45], Field::Seconds.parse("5/20", None).unwrap());
+    assert_eq!(
+        vec![0, 10, 20, 30],
+        Field::Minutes.parse("0-30/10,20", None).unwrap()
+    );
+}
+
+#[test]
+fn names_are_case_insensitive() {
+    assert_eq!(
+        vec![1, 2, 3, 4, 5],
+        Field::DaysOfWeek
+            .parse("Mon,tue,WED,Thu,fri", None)
+            .unwrap()
+    );
+    assert_eq!(
+        vec![1, 2, 3, 4, 5],
+        Field::DaysOfWeek.parse("Mon-Fri", None).unwrap()
+    );
+    assert_eq!(vec![6, 7, 8], Field::Months.parse("jun-AUG", None).unwrap());
+}
+
+#[test]
+fn sunday_can_be_seven() {
+    assert_eq!(vec![0, 6], Field::DaysOfWeek.parse("6-7", None).unwrap());
+    assert_eq!(vec![0], Field::DaysOfWeek.parse("Sun,7", None).unwrap());
+}
+
+#[test]
+fn names_only_allowed_for_months_and_weekdays() {
+    assert!(matches!(
+        Field::Hours.parse("Mon", None),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(matches!(
+        Field::Months.parse("Mon", None),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(matches!(
+        Field::DaysOfWeek.parse("Jan", None),
+        Err(Error::InvalidField { .. })
+    ));
+}
+
+#[test]
+fn out_of_range_values_are_rejected() {
+    assert!(matches!(
+        Field::Seconds.parse("60", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::Hours.parse("0-24", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::DaysOfMonth.parse("0", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::Months.parse("13", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::DaysOfWeek.parse("8", None),
+        Err(Error::OutOfRange { .. })
+    ));
+}
+
+#[test]
+fn malformed_items_are_rejected() {
+    for field in [
+        "", ",", "1,", "5-", "-5", "*/0", "*/", "10-5", "1-2-3", "a", "*/x",
+    ] {
+        assert!(
+            matches!(
+                Field::Minutes.parse(field, None),
+                Err(Error::InvalidField { .. })
+            ),
+            "{field:?} should not parse"
+        );
+    }
+}
+
+#[test]
+fn six_fields_builds_a_table() {
+    let fields = split("00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri");
+    assert!(six_fields(&fields, None).is_ok());
+
+    let fields = split("0 0 0 1,15 * Mon");
+    assert!(six_fields(&fields, None).is_ok());
+
+    let fields = split("0 0 0 * *");
+    assert!(matches!(
+        six_fields(&fields, None),
+        Err(Error::WrongNumberOfFields { .. })
+    ));
+}
+
+#[test]
+fn schedule_parses_six_fields() {
+    assert!("00 16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri"
+        .parse::<Schedule>()
+        .is_ok());
+    assert!("*/10 * * * * *".parse::<Schedule>().is_ok());
+    assert!("0 30 9 1-7 Jan-Jun/2 *".parse::<Schedule>().is_ok());
+    assert!(matches!(
+        "0 0 25 * * *".parse::<Schedule>(),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        "0 0 0 * * Someday".parse::<Schedule>(),
+        Err(Error::InvalidField { .. })
+    ));
+}
+
+#[test]
+fn five_fields_builds_a_table() {
+    let fields = split("16 13-18 * * Mon-Fri");
+    assert!(five_fields(&fields, None).is_ok());
+
+    let fields = split("60 * * * *");
+    assert!(matches!(
+        five_fields(&fields, None),
+        Err(Error::OutOfRange { .. })
+    ));
+
+    let fields = split("0 0 * * * *");
+    assert!(matches!(
+        five_fields(&fields, None),
+        Err(Error::WrongNumberOfFields { .. })
+    ));
+}
+
+#[test]
+fn schedule_parses_five_fields() {
+    assert!("16 13,14,15,16,17,18 * * Mon,Tue,Wed,Thu,Fri"
+        .parse::<Schedule>()
+        .is_ok());
+    assert!("*/5 * * * *".parse::<Schedule>().is_ok());
+    assert!(matches!(
+        "0 24 * * *".parse::<Schedule>(),
+        Err(Error::OutOfRange { .. })
+    ));
+}
+
+#[test]
+fn bad_expressions_return_errors() {
+    for expression in [
+        "* * * *",
+        "* * * * * * * *",
+        "a b c d e",
+        "*/ * * * *",
+        "1-2-3 * * * *",
+        ",,, * * * *",
+        "- - - - -",
+        "* * 0 * *",
+        "* * * 0 *",
+        "255 * * * *",
+        "256 * * * *",
+        "99999999999 * * * *",
+        "* * * * */-1",
+        "\u{e9} * * * *",
+    ] {
+        assert!(
+            expression.parse::<Schedule>().is_err(),
+            "{expression:?} should not parse"
+        );
+    }
+}
+
+#[test]
+fn errors_point_at_the_token() {
+    let error = "0 0 24 * * *".parse::<Schedule>().unwrap_err();
+    assert_eq!(
+        Error::OutOfRange {
+            field: Field::Hours,
+            token: "24".to_owned(),
+            span: 4..6,
+            min: 0,
+            max: 23,
+        },
+        error
+    );
+    assert_eq!("hour 24 out of range 0-23 at column 5", error.to_string());
+
+    let error = "*/5  0  1-2,3-x * * *".parse::<Schedule>().unwrap_err();
+    assert_eq!(Some(14..15), error.span());
+    assert_eq!("invalid hour `x` at column 15", error.to_string());
+
+    let error = "0 9 * * Mon#6".parse::<Schedule>().unwrap_err();
+    assert_eq!(Some(12..13), error.span());
+    assert_eq!(
+        "day of the week 6 out of range 1-5 at column 13",
+        error.to_string()
+    );
+
+    let error = "0 0 12 L-40 * ?".parse::<Schedule>().unwrap_err();
+    assert_eq!(Some(9..11), error.span());
+
+    let error = "0 0 0 1 1 * 2200".parse::<Schedule>().unwrap_err();
+    assert_eq!(
+        "year 2200 out of range 1970-2099 at column 13",
+        error.to_string()
+    );
+}
+
+#[test]
+fn wrong_number_of_fields_is_counted() {
+    assert_eq!(
+        Err(Error::WrongNumberOfFields { found: 4 }),
+        "* * * *".parse::<Schedule>().map(|_| ())
+    );
+    assert_eq!(Err(Error::Empty), " ".parse::<Schedule>().map(|_| ()));
+}
+
+#[test]
+fn years_have_their_own_range() {
+    assert_eq!(
+        vec![2023, 2024],
+        Field::parse_years("2023-2024", None).unwrap()
+    );
+    assert_eq!(
+        vec![2020, 2030],
+        Field::parse_years("2030,2020", None).unwrap()
+    );
+    assert!(matches!(
+        Field::parse_years("1969", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::parse_years("2100", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::Seconds.parse("2023", None),
+        Err(Error::OutOfRange { .. })
+    ));
+}
+
+#[test]
+fn seven_fields_builds_a_table() {
+    let fields = split("0 16 13 * * Mon-Fri 2023-2024");
+    assert!(seven_fields(&fields, None).is_ok());
+
+    let fields = split("0 16 13 * * Mon-Fri *");
+    assert!(seven_fields(&fields, None).is_ok());
+
+    let fields = split("0 16 13 * * Mon-Fri 23");
+    assert!(matches!(
+        seven_fields(&fields, None),
+        Err(Error::OutOfRange { .. })
+    ));
+}
+
+#[test]
+fn day_of_month_modifiers() {
+    use crate::schedule::fields::MonthDayModifier::*;
+
+    assert_eq!(
+        (vec![1], vec![Last { offset: 0 }]),
+        Field::parse_days_of_month("1,L", None).unwrap()
+    );
+    assert_eq!(
+        (
+            vec![],
+            vec![Last { offset: 3 }, LastWeekday, NearestWeekday(15)]
+        ),
+        Field::parse_days_of_month("L-3,lw,15W", None).unwrap()
+    );
+    assert!(matches!(
+        Field::parse_days_of_month("L-31", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::parse_days_of_month("32W", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::parse_days_of_month("W", None),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(matches!(
+        Field::parse_days_of_month("5#1", None),
+        Err(Error::InvalidField { .. })
+    ));
+}
+
+#[test]
+fn day_of_week_modifiers() {
+    use crate::schedule::fields::WeekdayModifier::*;
+
+    assert_eq!(
+        (vec![], vec![Last(5)]),
+        Field::parse_days_of_week("5L", None).unwrap()
+    );
+    assert_eq!(
+        (
+            vec![1],
+            vec![Nth { weekday: 2, nth: 1 }, Nth { weekday: 0, nth: 3 }]
+        ),
+        Field::parse_days_of_week("Mon,Tue#1,7#3", None).unwrap()
+    );
+    assert_eq!(
+        (vec![], vec![Last(5)]),
+        Field::parse_days_of_week("friL", None).unwrap()
+    );
+    assert!(matches!(
+        Field::parse_days_of_week("2#6", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::parse_days_of_week("8L", None),
+        Err(Error::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::parse_days_of_week("L", None),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(matches!(
+        Field::parse_days_of_week("15W", None),
+        Err(Error::InvalidField { .. })
+    ));
+}
+
+#[test]
+fn question_mark_only_in_day_fields() {
+    assert_eq!(
+        (1..=31).collect::<Vec<u8>>(),
+        Field::parse_days_of_month("?", None).unwrap().0
+    );
+    assert_eq!(
+        (0..7).collect::<Vec<u8>>(),
+        Field::parse_days_of_week("?", None).unwrap().0
+    );
+    assert!(matches!(
+        Field::Hours.parse("?", None),
+        Err(Error::InvalidField { .. })
+    ));
+}
+
+#[test]
+fn stepped_day_fields_keep_their_values() {
+    use chrono::{Datelike, TimeZone, Utc, Weekday};
+
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let days = |expression: &str| -> Vec<(u32, Weekday)> {
+        let schedule: Schedule = expression.parse().unwrap();
+        assert_eq!(expression, schedule.to_string());
+        schedule
+            .iter_from(&start)
+            .take(5)
+            .map(|datetime| (datetime.day(), datetime.weekday()))
+            .collect()
+    };
+    // Odd days of the month, along with every Monday.
+    assert_eq!(
+        [
+            (3, Weekday::Sat),
+            (5, Weekday::Mon),
+            (7, Weekday::Wed),
+            (9, Weekday::Fri),
+            (11, Weekday::Sun),
+        ][..],
+        days("0 0 0 */2 * Mon")
+    );
+    let schedule: Schedule = "0 0 0 */2 * Mon".parse().unwrap();
+    assert!(schedule.matches(&Utc.with_ymd_and_hms(2023, 6, 12, 0, 0, 0).unwrap()));
+    assert!(!schedule.matches(&Utc.with_ymd_and_hms(2023, 6, 14, 0, 0, 0).unwrap()));
+    // The 1st, along with every other day of the week from Sunday.
+    assert_eq!(
+        [
+            (3, Weekday::Sat),
+            (4, Weekday::Sun),
+            (6, Weekday::Tue),
+            (8, Weekday::Thu),
+            (10, Weekday::Sat),
+        ][..],
+        days("0 0 0 1 * */2")
+    );
+    // The 1st of September 2023 is a Friday.
+    let schedule: Schedule = "0 0 0 1 * */2".parse().unwrap();
+    assert!(schedule.matches(&Utc.with_ymd_and_hms(2023, 9, 1, 0, 0, 0).unwrap()));
+}
+
+#[test]
+fn schedule_parses_modifiers() {
+    for expression in [
+        "0 0 9 ? * 1#1",
+        "0 0 17 LW * ?",
+        "0 0 12 L * *",
+        "0 0 12 15W * ?",
+        "0 0 12 ? * 5L",
+        "0 0 12 1,L * Mon#2",
+    ] {
+        assert!(
+            expression.parse::<Schedule>().is_ok(),
+            "{expression:?} should parse"
+        );
+    }
+}
+
+#[test]
+fn hash_is_stable_for_a_key() {
+    let first = Field::Minutes.parse("H", Some("backup")).unwrap();
+    assert_eq!(1, first.len());
+    assert_eq!(first, Field::Minutes.parse("H", Some("backup")).unwrap());
+
+    // Spread out over the field, rather than all landing in one spot.
+    let picked: BTreeSet<_> = (0..100)
+        .map(|job| {
+            Field::Minutes
+                .parse("H", Some(&format!("job-{job}")))
+                .unwrap()
+        })
+        .collect();
+    assert!(picked.len() > 30, "only {} minutes picked", picked.len());
+}
+
+#[test]
+fn hash_stays_in_its_range() {
+    for job in 0..200 {
+        let key = format!("job-{job}");
+        let hours = Field::Hours.parse("H(9-17)", Some(&key)).unwrap();
+        assert!(matches!(hours[..], [9..=17]), "{hours:?}");
+        let (days, _) = Field::parse_days_of_month("H", Some(&key)).unwrap();
+        assert!(matches!(days[..], [1..=28]), "{days:?}");
+        let (weekdays, _) = Field::parse_days_of_week("H", Some(&key)).unwrap();
+        assert!(matches!(weekdays[..], [0..=6]), "{weekdays:?}");
+    }
+}
+
+#[test]
+fn hash_picks_where_a_step_starts() {
+    for job in 0..200 {
+        let key = format!("job-{job}");
+        let mins = Field::Minutes.parse("H/15", Some(&key)).unwrap();
+        assert_eq!(4, mins.len(), "{mins:?}");
+        assert!(mins[0] < 15);
+        assert!(mins.windows(2).all(|pair| pair[1] - pair[0] == 15));
+
+        let hours = Field::Hours
+            .parse("H(8-11)/2,H(20-21)", Some(&key))
+            .unwrap();
+        assert_eq!(3, hours.len(), "{hours:?}");
+        assert!(matches!(hours[..], [8 | 9, 10 | 11, 20 | 21]), "{hours:?}");
+    }
+}
+
+#[test]
+fn hash_differs_between_fields() {
+    let fields: Vec<_> = [Field::Seconds, Field::Minutes]
+        .into_iter()
+        .map(|field| {
+            (0..20)
+                .map(|job| field.parse("H", Some(&format!("job-{job}"))).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_ne!(fields[0], fields[1]);
+}
+
+#[test]
+fn hash_needs_a_key() {
+    assert_eq!(
+        Err(Error::MissingHashKey {
+            token: "H(1-5)".into(),
+            span: 2..8,
+        }),
+        Field::Hours.parse("0,H(1-5)", None)
+    );
+    assert!(matches!(
+        Field::Hours.parse("H(5-1)", Some("job")),
+        Err(Error::InvalidField { .. })
+    ));
+    assert!(matches!(
+        Field::Hours.parse("H(1-24)", Some("job")),
+        Err(Error::OutOfRange { span, .. }) if span == (4..6)
+    ));
+    assert!(matches!(
+        Field::Hours.parse("H1-5", Some("job")),
+        Err(Error::InvalidField { .. })
+    ));
+}
+
+#[test]
+fn hash_is_not_allowed_in_the_year_field() {
+    for years in ["H", "H(2030-2040)", "2024,H"] {
+        assert!(
+            matches!(
+                Field::parse_years(years, Some("job")),
+                Err(Error::InvalidField {
+                    field: Field::Years,
+                    ..
+                })
+            ),
+            "{years}"
+        );
+    }
+
+    let error = Schedule::parse_with_key("0 0 0 1 1 * H", "job").unwrap_err();
+    assert_eq!("invalid year `H` at column 13", error.to_string());
+}
@@ -0,0 +1,380 @@
+use crate::Error;
+use chrono::{DateTime, Duration, FixedOffset, Months, TimeZone, Utc};
+use std::{fmt, str::FromStr};
+
+/// A schedule that fires a fixed step apart, counting from a
+/// start that's pinned to an instant, written as an ISO 8601
+/// repeating interval like `R10/2023-06-01T13:16:00-05:00/PT1H`.
+///
+/// The start carries its own UTC offset, so days are always
+/// 24 hours long. Months and years are counted on the calendar
+/// from the start, and a day that a month doesn't have gets
+/// clamped to its last day, so the step after January 31st
+/// with `P1M` is the end of February, and the one after that
+/// is March 31st again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Repeating {
+    start: DateTime<FixedOffset>,
+    step: Step,
+    /// How many times it fires, or `None` to keep going forever.
+    count: Option<u64>,
+}
+
+/// How far apart the times are: a number of calendar
+/// months, and then a number of seconds on top of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Step {
+    months: u32,
+    seconds: i64,
+}
+
+/// About how many seconds a month has,
+/// going by the 400-year Gregorian cycle.
+const MONTH_SECONDS: i64 = 2_629_746;
+
+impl Repeating {
+    /// Returns the datetime of the `index`th time, counting from 0,
+    /// whether or not the count has run out by then.
+    fn nth(&self, index: u64) -> Option<DateTime<FixedOffset>> {
+        let months = u32::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_mul(self.step.months))?;
+        let seconds = i64::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_mul(self.step.seconds))
+            .filter(|seconds| seconds.abs() <= i64::MAX / 1_000)?;
+        self.start
+            .checked_add_months(Months::new(months))?
+            .checked_add_signed(Duration::seconds(seconds))
+    }
+
+    /// Returns the index of the first time at or after `instant`,
+    /// whether or not the count has run out by then, or `None`
+    /// if it would be too far off for a `DateTime` to hold.
+    fn first_from<Tz: TimeZone>(&self, instant: &DateTime<Tz>) -> Option<u64> {
+        // Months are close enough to the same length that a guess
+        // from the average only ever misses by a step or so.
+        let step = i64::from(self.step.months) * MONTH_SECONDS + self.step.seconds;
+        let elapsed = (instant.clone().with_timezone(&Utc) - self.start.with_timezone(&Utc))
+            .num_seconds()
+            .max(0);
+        let mut index = (elapsed / step) as u64;
+        while index > 0 && self.nth(index - 1)? >= *instant {
+            index -= 1;
+        }
+        while self.nth(index)? < *instant {
+            index += 1;
+        }
+        Some(index)
+    }
+
+    /// The index of the time that the count runs out at,
+    /// which is as far as `first_from` can go.
+    fn end(&self) -> u64 {
+        self.count.unwrap_or(u64::MAX)
+    }
+
+    /// Returns the first datetime strictly after `datetime`.
+    pub fn after<Tz: TimeZone>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let index = self.first_from(&(datetime.clone() + Duration::nanoseconds(1)))?;
+        self.get(index, &datetime.timezone())
+    }
+
+    /// Returns the last datetime strictly before `datetime`.
+    pub fn before<Tz: TimeZone>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let index = self.first_from(datetime).unwrap_or(u64::MAX);
+        let last = index.min(self.end()).checked_sub(1)?;
+        self.get(last, &datetime.timezone())
+    }
+
+    /// Returns true if it fires at some point during the
+    /// second that `datetime` is in.
+    pub fn contains<Tz: TimeZone>(&self, datetime: &DateTime<Tz>) -> bool {
+        let second = super::count::whole_seconds(datetime);
+        let next = second.clone() + Duration::seconds(1);
+        self.first_from(&second)
+            .and_then(|index| self.get(index, &datetime.timezone()))
+            .is_some_and(|datetime| datetime < next)
+    }
+
+    /// Returns how many times it fires after `start`,
+    /// up to and including `end`.
+    pub fn count_between<Tz: TimeZone>(&self, start: &DateTime<Tz>, end: &DateTime<Tz>) -> u64 {
+        let index = |datetime: &DateTime<Tz>| {
+            let next = datetime.clone() + Duration::nanoseconds(1);
+            self.first_from(&next).unwrap_or(u64::MAX).min(self.end())
+        };
+        index(end).saturating_sub(index(start))
+    }
+
+    /// Returns the `n`th datetime after `start`, counting from 1.
+    pub fn nth_after<Tz: TimeZone>(&self, start: &DateTime<Tz>, n: u64) -> Option<DateTime<Tz>> {
+        let first = self.first_from(&(start.clone() + Duration::nanoseconds(1)))?;
+        let index = first.checked_add(n.checked_sub(1)?)?;
+        self.get(index, &start.timezone())
+    }
+
+    /// Returns the `index`th datetime in `tz`, if
+    /// the count hasn't run out before it.
+    fn get<Tz: TimeZone>(&self, index: u64, tz: &Tz) -> Option<DateTime<Tz>> {
+        match index < self.end() {
+            true => self.nth(index).map(|datetime| datetime.with_timezone(tz)),
+            false => None,
+        }
+    }
+
+    /// Returns true if every time this fires, `other` fires too.
+    ///
+    /// A step of whole seconds lines up whenever the start is
+    /// one of `other`'s times, and the step is a whole number of
+    /// `other`'s. Months don't all have the same length, so with
+    /// them in the step, the two have to start at the same time.
+    pub fn is_subset_of(&self, other: &Repeating) -> bool {
+        let multiple = match (self.step.months, other.step.months) {
+            (0, 0) => match self.step.seconds % other.step.seconds {
+                0 => self.step.seconds / other.step.seconds,
+                _ => return false,
+            },
+            (_, 0) | (0, _) => return false,
+            (months, others) => match months % others {
+                0 if self.step.seconds * i64::from(others)
+                    == other.step.seconds * i64::from(months) =>
+                {
+                    i64::from(months / others)
+                }
+                _ => return false,
+            },
+        };
+        let first = match other.step.months {
+            0 => {
+                let offset = (self.start - other.start).num_seconds();
+                match offset >= 0 && offset % other.step.seconds == 0 {
+                    true => offset / other.step.seconds,
+                    false => return false,
+                }
+            }
+            _ if self.start == other.start => 0,
+            _ => return false,
+        };
+        match (self.count, other.count) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(count), Some(others)) => {
+                let last = (count as i64 - 1)
+                    .checked_mul(multiple)
+                    .and_then(|last| last.checked_add(first));
+                last.is_some_and(|last| (last as u64) < others)
+            }
+        }
+    }
+
+    /// Describes it in English, like "Every 1 hour, 10 times,
+    /// starting at 2023-06-01 13:16:00 -05:00".
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        let (years, months) = (self.step.months / 12, self.step.months % 12);
+        let (days, rest) = (self.step.seconds / 86_400, self.step.seconds % 86_400);
+        let (hours, minutes, seconds) = (rest / 3_600, rest % 3_600 / 60, rest % 60);
+        let units = [
+            (i64::from(years), "year"),
+            (i64::from(months), "month"),
+            (days, "day"),
+            (hours, "hour"),
+            (minutes, "minute"),
+            (seconds, "second"),
+        ];
+        for (count, name) in units {
+            match count {
+                0 => {}
+                1 => parts.push(format!("1 {name}")),
+                _ => parts.push(format!("{count} {name}s")),
+            }
+        }
+        let every = match parts.pop() {
+            Some(last) if parts.is_empty() => format!("Every {last}"),
+            Some(last) => format!("Every {} and {last}", parts.join(", ")),
+            None => "Every 0 seconds".to_owned(),
+        };
+        let start = self.start.format("%Y-%m-%d %H:%M:%S %:z");
+        match self.count {
+            Some(1) => format!("Once, at {start}"),
+            Some(count) => format!("{every}, {count} times, starting at {start}"),
+            None => format!("{every}, starting at {start}"),
+        }
+    }
+}
+
+impl FromStr for Repeating {
+    type Err = Error;
+
+    /// Parses a repeating interval made up of three parts split
+    /// up by `/`. The first is `R` followed by how many times it
+    /// fires, with nothing or `-1` to keep going forever. Then
+    /// comes the start, with a UTC offset, and then either the
+    /// step, like `P1D` or `PT1H30M`, or the end of the first
+    /// step, which makes the step the time in between.
+    ///
+    /// ISO 8601 also lets the step come before an end instead of
+    /// after a start, which counts backwards from the end, and
+    /// that isn't supported.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |token: &str, at: usize| Error::InvalidRepeatingInterval {
+            token: token.to_owned(),
+            span: at..at + token.len(),
+        };
+        if s.trim().is_empty() {
+            return Err(Error::Empty);
+        }
+        let parts: Vec<&str> = s.split('/').collect();
+        let [repeat, start, end] = parts[..] else {
+            return Err(invalid(s, 0));
+        };
+        let (start_at, end_at) = (repeat.len() + 1, repeat.len() + start.len() + 2);
+
+        let count = match repeat.strip_prefix('R') {
+            Some("" | "-1") => None,
+            Some(count) if count.bytes().all(|b| b.is_ascii_digit()) => {
+                Some(count.parse().map_err(|_| invalid(repeat, 0))?)
+            }
+            _ => return Err(invalid(repeat, 0)),
+        };
+        if start.starts_with('P') {
+            return Err(Error::Unsupported {
+                token: start.to_owned(),
+                span: start_at..start_at + start.len(),
+            });
+        }
+        let start = datetime(start).ok_or_else(|| invalid(start, start_at))?;
+        let step = match end.starts_with('P') {
+            true => step(end),
+            false => datetime(end).map(|end| Step {
+                months: 0,
+                seconds: (end - start).num_seconds(),
+            }),
+        };
+        let step = step
+            .filter(|step| step.months > 0 || step.seconds > 0)
+            .ok_or_else(|| invalid(end, end_at))?;
+        if count == Some(0) {
+            return Err(Error::NoRepetitions {
+                token: repeat.to_owned(),
+                span: 0..repeat.len(),
+            });
+        }
+        Ok(Repeating { start, step, count })
+    }
+}
+
+/// Parses a datetime with a UTC offset, in either the extended
+/// format like `2023-06-01T13:16:00-05:00`, or the basic one like
+/// `20230601T131600Z`. The seconds can be left out.
+fn datetime(s: &str) -> Option<DateTime<FixedOffset>> {
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y-%m-%dT%H:%M%z",
+        "%Y%m%dT%H%M%S%z",
+        "%Y%m%dT%H%M%z",
+    ];
+    let s = match s.strip_suffix('Z') {
+        Some(s) => format!("{s}+00:00"),
+        None => s.to_owned(),
+    };
+    FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(&s, format).ok())
+}
+
+/// Parses a duration like `P1Y2M10DT2H30M` or `P2W`. Each number
+/// is followed by its unit, and the units have to go from largest
+/// to smallest, with the hours, minutes and seconds after a `T`.
+fn step(s: &str) -> Option<Step> {
+    let (date, time) = match s.strip_prefix('P')?.split_once('T') {
+        Some((_, "")) => return None,
+        Some((date, time)) => (date, time),
+        None => (s.strip_prefix('P')?, ""),
+    };
+    let mut step = Step {
+        months: 0,
+        seconds: 0,
+    };
+    for (number, unit) in units(date, &['Y', 'M', 'W', 'D'])? {
+        match unit {
+            'Y' => {
+                step.months = step
+                    .months
+                    .checked_add(u32::try_from(number).ok()?.checked_mul(12)?)?
+            }
+            'M' => step.months = step.months.checked_add(u32::try_from(number).ok()?)?,
+            'W' => step.seconds = step.seconds.checked_add(number.checked_mul(604_800)?)?,
+            _ => step.seconds = step.seconds.checked_add(number.checked_mul(86_400)?)?,
+        }
+    }
+    for (number, unit) in units(time, &['H', 'M', 'S'])? {
+        let seconds = match unit {
+            'H' => 3_600,
+            'M' => 60,
+            _ => 1,
+        };
+        step.seconds = step.seconds.checked_add(number.checked_mul(seconds)?)?;
+    }
+    // Anything bigger would overflow once it's a `Duration`.
+    (step.seconds <= i64::MAX / 1_000).then_some(step)
+}
+
+/// Splits `s` into numbers, each followed by one of `names`,
+/// which have to come in the same order as in `names`.
+fn units(s: &str, names: &[char]) -> Option<Vec<(i64, char)>> {
+    let mut names = names.iter();
+    let mut units = Vec::new();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let (number, tail) = rest.split_at(digits);
+        let number = number.parse::<i64>().ok()?;
+        let mut chars = tail.chars();
+        let unit = chars.next()?;
+        names.find(|name| **name == unit)?;
+        units.push((number, unit));
+        rest = chars.as_str();
+    }
+    Some(units)
+}
+
+/// Writes it back out with the step in the largest units that
+/// fit, so `PT36H` comes back out as `P1DT12H`.
+impl fmt::Display for Repeating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.count {
+            Some(count) => write!(f, "R{count}/")?,
+            None => write!(f, "R/")?,
+        }
+        write!(f, "{}/P", self.start.format("%Y-%m-%dT%H:%M:%S%.f%:z"))?;
+        let (years, months) = (self.step.months / 12, self.step.months % 12);
+        let (days, rest) = (self.step.seconds / 86_400, self.step.seconds % 86_400);
+        for (count, unit) in [
+            (i64::from(years), 'Y'),
+            (i64::from(months), 'M'),
+            (days, 'D'),
+        ] {
+            if count > 0 {
+                write!(f, "{count}{unit}")?;
+            }
+        }
+        if rest > 0 {
+            f.write_str("T")?;
+            for (count, unit) in [
+                (rest / 3_600, 'H'),
+                (rest % 3_600 / 60, 'M'),
+                (rest % 60, 'S'),
+            ] {
+                if count > 0 {
+                    write!(f, "{count}{unit}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,238 @@
+use super::Repeating;
+use crate::Error;
+use chrono::{DateTime, TimeZone, Utc};
+
+fn repeating(s: &str) -> Repeating {
+    s.parse().unwrap_or_else(|error| panic!("{s:?}: {error}"))
+}
+
+fn utc(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+/// Every time it runs strictly after `start`, in UTC.
+fn times(repeating: &Repeating, start: &str) -> Vec<String> {
+    let mut times = Vec::new();
+    let mut current = utc(start);
+    while let Some(next) = repeating.after(&current) {
+        times.push(next.to_rfc3339());
+        current = next;
+        if times.len() > 20 {
+            break;
+        }
+    }
+    times
+}
+
+#[test]
+fn bounded_repetitions_stop() {
+    let hourly = repeating("R3/2023-06-01T13:16:00-05:00/PT1H");
+    assert_eq!(
+        [
+            "2023-06-01T18:16:00+00:00",
+            "2023-06-01T19:16:00+00:00",
+            "2023-06-01T20:16:00+00:00",
+        ][..],
+        times(&hourly, "2023-01-01T00:00:00Z")
+    );
+    assert_eq!(
+        ["2023-06-01T20:16:00+00:00"][..],
+        times(&hourly, "2023-06-01T19:16:00Z")
+    );
+    assert!(times(&hourly, "2023-06-01T20:16:00Z").is_empty());
+}
+
+#[test]
+fn unbounded_repetitions_keep_going() {
+    for daily in ["R/2023-06-01T00:00:00Z/P1D", "R-1/20230601T000000Z/P1D"] {
+        let daily = repeating(daily);
+        let start = utc("2050-03-04T12:00:00Z");
+        assert_eq!(Some(utc("2050-03-05T00:00:00Z")), daily.after(&start));
+        assert_eq!(Some(utc("2050-03-04T00:00:00Z")), daily.before(&start));
+    }
+}
+
+#[test]
+fn months_count_on_the_calendar() {
+    let monthly = repeating("R4/2023-01-31T09:00:00Z/P1M");
+    assert_eq!(
+        [
+            "2023-01-31T09:00:00+00:00",
+            "2023-02-28T09:00:00+00:00",
+            "2023-03-31T09:00:00+00:00",
+            "2023-04-30T09:00:00+00:00",
+        ][..],
+        times(&monthly, "2023-01-01T00:00:00Z")
+    );
+    let yearly = repeating("R/2020-02-29T00:00:00Z/P1Y");
+    assert_eq!(
+        Some(utc("2024-02-29T00:00:00Z")),
+        yearly.after(&utc("2023-06-01T00:00:00Z"))
+    );
+}
+
+#[test]
+fn an_end_takes_the_place_of_the_step() {
+    let repeating = repeating("R/2023-06-01T09:00Z/2023-06-01T10:30Z");
+    assert_eq!("R/2023-06-01T09:00:00+00:00/PT1H30M", repeating.to_string());
+}
+
+#[test]
+fn counts_and_finds_the_nth() {
+    let hourly = repeating("R10/2023-06-01T00:00:00Z/PT1H");
+    let (start, end) = (utc("2023-05-01T00:00:00Z"), utc("2023-07-01T00:00:00Z"));
+    assert_eq!(10, hourly.count_between(&start, &end));
+    assert_eq!(
+        3,
+        hourly.count_between(&utc("2023-06-01T00:00:00Z"), &utc("2023-06-01T03:00:00Z"))
+    );
+    assert_eq!(
+        Some(utc("2023-06-01T09:00:00Z")),
+        hourly.nth_after(&start, 10)
+    );
+    assert_eq!(None, hourly.nth_after(&start, 11));
+    assert_eq!(None, hourly.nth_after(&start, 0));
+}
+
+#[test]
+fn contains_the_seconds_it_runs_in() {
+    let hourly = repeating("R10/2023-06-01T00:00:00Z/PT1H");
+    assert!(hourly.contains(&utc("2023-06-01T05:00:00.5Z")));
+    assert!(!hourly.contains(&utc("2023-06-01T05:00:01Z")));
+    assert!(!hourly.contains(&utc("2023-06-01T10:00:00Z")));
+    assert!(!hourly.contains(&utc("2023-05-31T23:00:00Z")));
+}
+
+#[test]
+fn writes_the_step_in_the_largest_units() {
+    for (written, expected) in [
+        (
+            "R10/2023-06-01T13:16:00-05:00/PT1H",
+            "R10/2023-06-01T13:16:00-05:00/PT1H",
+        ),
+        (
+            "R/2023-06-01T00:00:00Z/PT36H",
+            "R/2023-06-01T00:00:00+00:00/P1DT12H",
+        ),
+        (
+            "R5/20230601T0000+0200/P1Y14M2W",
+            "R5/2023-06-01T00:00:00+02:00/P2Y2M14D",
+        ),
+        (
+            "R2/2023-06-01T00:00:00.25Z/PT90S",
+            "R2/2023-06-01T00:00:00.250+00:00/PT1M30S",
+        ),
+    ] {
+        assert_eq!(expected, repeating(written).to_string(), "{written}");
+        assert_eq!(repeating(written), repeating(expected));
+    }
+}
+
+#[test]
+fn describes_the_step_and_start() {
+    assert_eq!(
+        "Every 1 hour, 10 times, starting at 2023-06-01 13:16:00 -05:00",
+        repeating("R10/2023-06-01T13:16:00-05:00/PT1H").describe()
+    );
+    assert_eq!(
+        "Every 1 month and 2 days, starting at 2023-06-01 00:00:00 +00:00",
+        repeating("R/2023-06-01T00:00:00Z/P1M2D").describe()
+    );
+    assert_eq!(
+        "Once, at 2023-06-01 00:00:00 +00:00",
+        repeating("R1/2023-06-01T00:00:00Z/P1D").describe()
+    );
+}
+
+#[test]
+fn is_subset_of_lines_up_the_steps() {
+    let subset = |a: &str, b: &str| repeating(a).is_subset_of(&repeating(b));
+    assert!(subset(
+        "R/2023-06-01T02:00:00Z/PT2H",
+        "R/2023-06-01T00:00:00Z/PT1H"
+    ));
+    assert!(!subset(
+        "R/2023-06-01T00:30:00Z/PT2H",
+        "R/2023-06-01T00:00:00Z/PT1H"
+    ));
+    assert!(!subset(
+        "R/2023-06-01T00:00:00Z/PT90M",
+        "R/2023-06-01T00:00:00Z/PT1H"
+    ));
+    assert!(subset(
+        "R3/2023-06-01T00:00:00Z/PT2H",
+        "R5/2023-06-01T00:00:00Z/PT1H"
+    ));
+    assert!(!subset(
+        "R4/2023-06-01T00:00:00Z/PT2H",
+        "R5/2023-06-01T00:00:00Z/PT1H"
+    ));
+    assert!(!subset(
+        "R/2023-06-01T00:00:00Z/PT2H",
+        "R5/2023-06-01T00:00:00Z/PT1H"
+    ));
+    assert!(subset(
+        "R/2023-01-31T00:00:00Z/P2M",
+        "R/2023-01-31T00:00:00Z/P1M"
+    ));
+    assert!(!subset(
+        "R/2023-02-28T00:00:00Z/P2M",
+        "R/2023-01-31T00:00:00Z/P1M"
+    ));
+    assert!(!subset(
+        "R/2023-01-31T00:00:00Z/P1M",
+        "R/2023-01-31T00:00:00Z/P30D"
+    ));
+}
+
+#[test]
+fn malformed_intervals() {
+    let error = |s: &str| s.parse::<Repeating>().unwrap_err();
+    let span = |s: &str| error(s).span();
+    assert_eq!(Some(0..3), span("X10/2023-06-01T00:00:00Z/PT1H"));
+    assert_eq!(Some(0..3), span("R1x/2023-06-01T00:00:00Z/PT1H"));
+    assert_eq!(Some(4..14), span("R10/2023-06-01/PT1H"));
+    assert_eq!(Some(4..23), span("R10/2023-06-01T00:00:00/PT1H"));
+    for step in [
+        "PT", "P", "PT0S", "P1H", "PT1D", "PT30M1H", "P1.5D", "PT1H1H",
+    ] {
+        let s = format!("R/2023-06-01T00:00:00Z/{step}");
+        assert_eq!(Some(23..23 + step.len()), span(&s), "{s}");
+    }
+    assert_eq!(
+        Some(23..43),
+        span("R/2023-06-01T00:00:00Z/2023-05-01T00:00:00Z")
+    );
+    assert_eq!(Some(0..22), span("R/2023-06-01T00:00:00Z"));
+    assert_eq!(
+        Error::Unsupported {
+            token: "PT1H".to_owned(),
+            span: 2..6,
+        },
+        error("R/PT1H/2023-06-01T00:00:00Z")
+    );
+    let never = error("R0/2023-06-01T00:00:00Z/PT1H");
+    assert_eq!(
+        Error::NoRepetitions {
+            token: "R0".into(),
+            span: 0..2,
+        },
+        never
+    );
+    assert_eq!(
+        "`R0` at column 1 repeats zero times, so the schedule never runs",
+        never.to_string()
+    );
+    assert_eq!(Error::Empty, error(""));
+}
+
+#[test]
+fn works_in_any_timezone() {
+    let hourly = repeating("R/2023-06-01T13:16:00-05:00/PT1H");
+    let start = chrono::FixedOffset::east_opt(9 * 3_600)
+        .unwrap()
+        .with_ymd_and_hms(2023, 6, 2, 9, 0, 0)
+        .unwrap();
+    let next = hourly.after(&start).unwrap();
+    assert_eq!("2023-06-02T09:16:00+09:00", next.to_rfc3339());
+}
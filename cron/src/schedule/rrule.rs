@@ -0,0 +1,425 @@
+//! Reads iCalendar (RFC 5545) recurrence rules, like
+//! `FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=13;BYMINUTE=16`,
+//! and writes schedules out as `.ics` events.
+//!
+//! A rule takes whatever it leaves out from the start of its
+//! event. Rules here don't have one, so anything finer than the
+//! frequency starts at its lowest value instead: a `DAILY` rule
+//! runs at midnight, a `WEEKLY` one on Mondays, a `MONTHLY` one
+//! on the 1st, and a `YEARLY` one on the 1st of January, unless
+//! it picks its own days, which then run in every month.
+
+use super::{
+    fields::{FieldTable, MonthDayModifier, WeekdayModifier},
+    parser::stable_hash,
+};
+use crate::{Error, Field};
+use chrono::{DateTime, Offset, TimeZone, Utc};
+use std::collections::BTreeSet;
+
+/// How a rule names the days of the week, starting from Sunday
+/// so that they line up with the way cron numbers them.
+pub(super) const RULE_WEEKDAYS: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+/// What a rule can start with when it's
+/// copied straight out of a calendar file.
+const PROPERTY_PREFIX: &str = "RRULE:";
+
+/// Rule parts that say when a rule stops, or pick days in ways
+/// that a `FieldTable` can't follow.
+const UNSUPPORTED: [&str; 5] = ["COUNT", "UNTIL", "BYSETPOS", "BYYEARDAY", "BYWEEKNO"];
+
+/// Content lines longer than this many bytes get folded.
+const LINE_LENGTH: usize = 75;
+
+/// How often a rule repeats, from most to least often.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Freq {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Freq {
+    const NAMES: [(&'static str, Freq); 7] = [
+        ("SECONDLY", Freq::Secondly),
+        ("MINUTELY", Freq::Minutely),
+        ("HOURLY", Freq::Hourly),
+        ("DAILY", Freq::Daily),
+        ("WEEKLY", Freq::Weekly),
+        ("MONTHLY", Freq::Monthly),
+        ("YEARLY", Freq::Yearly),
+    ];
+
+    /// The field that repeating at this frequency steps through,
+    /// and how many values it has, for the ones an `INTERVAL`
+    /// can step through evenly.
+    fn field(self) -> Option<(Field, u32)> {
+        match self {
+            Freq::Secondly => Some((Field::Seconds, 60)),
+            Freq::Minutely => Some((Field::Minutes, 60)),
+            Freq::Hourly => Some((Field::Hours, 24)),
+            Freq::Monthly => Some((Field::Months, 12)),
+            Freq::Daily | Freq::Weekly | Freq::Yearly => None,
+        }
+    }
+}
+
+/// A rule part along with where it starts, for errors.
+type Part<'a> = (usize, &'a str);
+
+/// The plain weekdays of a `BYDAY`, and the numbered
+/// ones along with their numbers.
+type Weekdays = (Vec<u8>, Vec<(u8, i32)>);
+
+/// The parts of a rule, as they were read.
+#[derive(Default)]
+struct Rule<'a> {
+    freq: Option<Freq>,
+    interval: Option<(u32, Part<'a>)>,
+    seconds: Option<Vec<u32>>,
+    minutes: Option<Vec<u32>>,
+    hours: Option<Vec<u32>>,
+    months: Option<Vec<u32>>,
+    month_days: Option<(Vec<u8>, Vec<MonthDayModifier>, Part<'a>)>,
+    weekdays: Option<(Weekdays, Part<'a>)>,
+}
+
+/// Parses a rule into a `FieldTable`. The rule can start with
+/// `RRULE:`, and the names in it can be in any case.
+pub(super) fn parse(rule: &str) -> Result<FieldTable, Error> {
+    let start = match rule.get(..PROPERTY_PREFIX.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(PROPERTY_PREFIX) => PROPERTY_PREFIX.len(),
+        _ => 0,
+    };
+    if rule[start..].trim().is_empty() {
+        return Err(Error::Empty);
+    }
+
+    let mut parsed = Rule::default();
+    let mut at = start;
+    for part in rule[start..].split(';') {
+        parsed.read((at, part))?;
+        at += part.len() + 1;
+    }
+    parsed.build(rule.len())
+}
+
+impl<'a> Rule<'a> {
+    /// Reads one `NAME=VALUE` part of a rule.
+    fn read(&mut self, (at, part): Part<'a>) -> Result<(), Error> {
+        let invalid = |token: &str, at: usize| Error::InvalidRule {
+            token: token.to_owned(),
+            span: at..at + token.len(),
+        };
+        let (name, value) = part.split_once('=').ok_or_else(|| invalid(part, at))?;
+        let value_at = at + name.len() + 1;
+        let shifted = |error: Error| error.shifted(value_at);
+
+        match name.trim().to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                let freq = Freq::NAMES
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(value))
+                    .ok_or_else(|| invalid(value, value_at))?;
+                self.freq = Some(freq.1);
+            }
+            "INTERVAL" => match value.parse::<u32>() {
+                Ok(interval) if interval > 0 => self.interval = Some((interval, (at, part))),
+                _ => return Err(invalid(value, value_at)),
+            },
+            "BYSECOND" => self.seconds = Some(values(Field::Seconds, value).map_err(shifted)?),
+            "BYMINUTE" => self.minutes = Some(values(Field::Minutes, value).map_err(shifted)?),
+            "BYHOUR" => self.hours = Some(values(Field::Hours, value).map_err(shifted)?),
+            "BYMONTH" => self.months = Some(values(Field::Months, value).map_err(shifted)?),
+            "BYMONTHDAY" => {
+                let (days, modifiers) = month_days(value).map_err(shifted)?;
+                self.month_days = Some((days, modifiers, (at, part)));
+            }
+            "BYDAY" => {
+                self.weekdays = Some((weekdays(value).map_err(shifted)?, (at, part)));
+            }
+            "WKST" => {
+                // The week start only matters to parts
+                // that aren't supported anyway.
+                weekday(value).map_err(shifted)?;
+            }
+            name if UNSUPPORTED.contains(&name) => return Err(unsupported((at, part))),
+            _ => return Err(invalid(part, at)),
+        }
+        Ok(())
+    }
+
+    /// Builds the `FieldTable` that the rule describes. The
+    /// rule is `end` bytes long, which is where a missing
+    /// `FREQ` gets reported.
+    fn build(self, end: usize) -> Result<FieldTable, Error> {
+        let freq = self.freq.ok_or(Error::InvalidRule {
+            token: String::new(),
+            span: end..end,
+        })?;
+        // An `INTERVAL` can only be a step when it goes evenly
+        // around its field, and nothing else picks from that field.
+        let step = match (self.interval, freq.field()) {
+            (None | Some((1, _)), _) => 1,
+            (Some((interval, part)), Some((field, values))) => {
+                let picked = match field {
+                    Field::Seconds => self.seconds.is_some(),
+                    Field::Minutes => self.minutes.is_some(),
+                    Field::Hours => self.hours.is_some(),
+                    _ => self.months.is_some(),
+                };
+                match values % interval == 0 && !picked {
+                    true => interval,
+                    false => return Err(unsupported(part)),
+                }
+            }
+            (Some((_, part)), None) => return Err(unsupported(part)),
+        };
+        let field = |field: Field, picked: Option<Vec<u32>>, level: Freq| match picked {
+            Some(values) => narrow(values),
+            None if freq > level => vec![field.min() as u8],
+            None => {
+                let step = if freq == level { step } else { 1 };
+                narrow((field.min()..=field.max()).step_by(step as usize).collect())
+            }
+        };
+
+        // A `YEARLY` rule that picks its days runs on them all
+        // year round, so only one without any falls back to January.
+        let picks_days = self.month_days.is_some() || self.weekdays.is_some();
+        let months = match self.months.clone() {
+            None if picks_days => (1..=12).collect(),
+            months => field(Field::Months, months, Freq::Monthly),
+        };
+
+        let mut builder = FieldTable::builder();
+        builder
+            .with_secs_iter(field(Field::Seconds, self.seconds, Freq::Secondly))
+            .with_mins_iter(field(Field::Minutes, self.minutes, Freq::Minutely))
+            .with_hrs_iter(field(Field::Hours, self.hours, Freq::Hourly))
+            .with_months_iter(months);
+
+        match (self.month_days, self.weekdays) {
+            (Some(_), Some((_, part))) => return Err(unsupported(part)),
+            (Some((_, _, part)), None) if freq == Freq::Weekly => return Err(unsupported(part)),
+            (Some((days, modifiers, _)), None) => {
+                builder
+                    .with_days_of_the_month_only_iter(days)
+                    .with_days_of_the_month_modifiers(modifiers);
+            }
+            (None, Some(((days, nths), part))) => {
+                // A numbered weekday is the nth one of the month, which
+                // needs a rule that goes month by month to make sense.
+                let monthly =
+                    freq == Freq::Monthly || (freq == Freq::Yearly && self.months.is_some());
+                if !nths.is_empty() && !monthly {
+                    return Err(unsupported(part));
+                }
+                let modifiers = nths
+                    .into_iter()
+                    .map(|(weekday, nth)| match nth {
+                        -1 => Ok(WeekdayModifier::Last(weekday)),
+                        1..=5 => Ok(WeekdayModifier::Nth {
+                            weekday,
+                            nth: nth as u8,
+                        }),
+                        _ => Err(unsupported(part)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                builder
+                    .with_days_of_the_week_only_iter(days)
+                    .with_days_of_the_week_modifiers(modifiers);
+            }
+            (None, None) => {
+                match freq {
+                    Freq::Weekly => builder.with_days_of_the_week_only(1),
+                    Freq::Monthly | Freq::Yearly => builder.with_days_of_the_month_only(1),
+                    _ => builder.with_days_of_the_month_only_iter(1..=31),
+                };
+            }
+        }
+        Ok(builder.build()?)
+    }
+}
+
+fn unsupported((at, part): Part) -> Error {
+    Error::Unsupported {
+        token: part.to_owned(),
+        span: at..at + part.len(),
+    }
+}
+
+/// Parses a comma-separated list of numbers, returning them sorted.
+fn values(field: Field, list: &str) -> Result<Vec<u32>, Error> {
+    let mut values = BTreeSet::new();
+    let mut at = 0;
+    for item in list.split(',') {
+        values.insert(field.value(item).map_err(|error| error.shifted(at))?);
+        at += item.len() + 1;
+    }
+    Ok(values.into_iter().collect())
+}
+
+/// Parses the days of a `BYMONTHDAY`, where negative
+/// days count back from the end of the month.
+fn month_days(list: &str) -> Result<(Vec<u8>, Vec<MonthDayModifier>), Error> {
+    let field = Field::DaysOfMonth;
+    let (mut days, mut modifiers) = (Vec::new(), Vec::new());
+    let mut at = 0;
+    for item in list.split(',') {
+        match item.strip_prefix('-') {
+            Some(day) => {
+                let day = field.value(day).map_err(|error| error.shifted(at + 1))?;
+                modifiers.push(MonthDayModifier::Last {
+                    offset: day as u8 - 1,
+                });
+            }
+            None => {
+                let day = item.strip_prefix('+').unwrap_or(item);
+                let sign = item.len() - day.len();
+                days.push(field.value(day).map_err(|error| error.shifted(at + sign))? as u8);
+            }
+        }
+        at += item.len() + 1;
+    }
+    Ok((days, modifiers))
+}
+
+/// Parses the days of a `BYDAY`, like `MO,FR` or `1MO,-1FR`,
+/// into the plain weekdays and the numbered ones.
+fn weekdays(list: &str) -> Result<Weekdays, Error> {
+    let (mut days, mut nths) = (Vec::new(), Vec::new());
+    let mut at = 0;
+    for item in list.split(',') {
+        // Every weekday is two letters, so anything
+        // in front of them is the number.
+        let split = match item.len().checked_sub(2) {
+            Some(split) if item.is_char_boundary(split) => split,
+            _ => 0,
+        };
+        let (nth, day) = item.split_at(split);
+        let day = weekday(day).map_err(|error| error.shifted(at + split))?;
+        match nth {
+            "" => days.push(day),
+            nth => match nth.parse::<i32>() {
+                Ok(nth) => nths.push((day, nth)),
+                Err(_) => return Err(Field::DaysOfWeek.invalid(nth).shifted(at)),
+            },
+        }
+        at += item.len() + 1;
+    }
+    Ok((days, nths))
+}
+
+/// Parses a two-letter weekday like `MO`.
+fn weekday(code: &str) -> Result<u8, Error> {
+    RULE_WEEKDAYS
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(code))
+        .map(|day| day as u8)
+        .ok_or_else(|| Field::DaysOfWeek.invalid(code))
+}
+
+/// Every field besides the years fits inside of a
+/// `u8`, once it's been checked to be in range.
+fn narrow(values: Vec<u32>) -> Vec<u8> {
+    values.into_iter().map(|value| value as u8).collect()
+}
+
+/// Writes `datetimes` out as a calendar with a single event
+/// that happens at each of them, summed up by `summary`.
+///
+/// The times are all written in UTC, so that they mean the same
+/// thing to everyone the calendar is shared with. When they're
+/// in UTC to begin with, `rule` can repeat the event for them,
+/// and otherwise they get listed out one by one, since a rule
+/// written in local time would drift once the offset changes.
+pub(super) fn ics<Tz: TimeZone>(
+    datetimes: &[DateTime<Tz>],
+    rule: Option<String>,
+    summary: &str,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//cron//Schedule//EN".to_owned(),
+    ];
+    let stamp = |datetime: &DateTime<Tz>| {
+        datetime
+            .with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string()
+    };
+    if let Some((first, rest)) = datetimes.split_first() {
+        // The UID has to stay the same for the same event, even
+        // across releases, or calendars would see a new one.
+        let event = format!(
+            "{summary}\n{}\n{}",
+            rule.as_deref().unwrap_or_default(),
+            datetimes.len()
+        );
+        let uid = stable_hash(event.bytes());
+
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:{}-{uid:016x}@cron", stamp(first)));
+        lines.push(format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")));
+        lines.push(format!("DTSTART:{}", stamp(first)));
+        let in_utc = datetimes
+            .iter()
+            .all(|datetime| datetime.offset().fix().local_minus_utc() == 0);
+        match rule.filter(|_| in_utc) {
+            Some(rule) => lines.push(format!("RRULE:{rule};COUNT={}", datetimes.len())),
+            None if rest.is_empty() => {}
+            None => {
+                let rest: Vec<String> = rest.iter().map(stamp).collect();
+                lines.push(format!("RDATE:{}", rest.join(",")));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", escape(summary)));
+        lines.push("END:VEVENT".to_owned());
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+/// Escapes the characters that mean something in a text value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Ends a content line, folding it onto more lines that
+/// start with a space if it's longer than `LINE_LENGTH`.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,225 @@
+use crate::{schedule::Schedule, Error};
+use chrono::{TimeZone, Utc};
+
+/// Parses a rule, and writes it back out as
+/// the cron expression it turned into.
+fn cron(rule: &str) -> String {
+    Schedule::from_rrule(rule)
+        .unwrap_or_else(|error| panic!("{rule:?}: {error}"))
+        .to_string()
+}
+
+fn rrule(expression: &str) -> Option<String> {
+    expression.parse::<Schedule>().unwrap().to_rrule()
+}
+
+#[test]
+fn parses_the_by_parts() {
+    assert_eq!(
+        "0 16 13-18 * * Mon-Fri",
+        cron("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=13,14,15,16,17,18;BYMINUTE=16")
+    );
+    assert_eq!(
+        "30 0 9 1,15 Jan,Jul *",
+        cron("RRULE:FREQ=MONTHLY;BYMONTH=1,7;BYMONTHDAY=15,1;BYHOUR=9;BYSECOND=30")
+    );
+    assert_eq!(
+        "0 0 9 * * Mon",
+        cron("freq=weekly;byday=mo;byhour=9;wkst=SU")
+    );
+}
+
+#[test]
+fn fills_in_what_the_rule_leaves_out() {
+    assert_eq!("* * * * * *", cron("FREQ=SECONDLY"));
+    assert_eq!("0 * * * * *", cron("FREQ=MINUTELY"));
+    assert_eq!("0 0 * * * *", cron("FREQ=HOURLY"));
+    assert_eq!("0 0 0 * * *", cron("FREQ=DAILY"));
+    assert_eq!("0 0 0 * * Mon", cron("FREQ=WEEKLY"));
+    assert_eq!("0 0 0 1 * *", cron("FREQ=MONTHLY"));
+    assert_eq!("0 0 0 1 Jan *", cron("FREQ=YEARLY"));
+    assert_eq!("0 0 0 1 Mar,Sep *", cron("FREQ=YEARLY;BYMONTH=3,9"));
+    // Days picked by a yearly rule run in every month of the year.
+    assert_eq!("0 0 0 * * Mon", cron("FREQ=YEARLY;BYDAY=MO"));
+    assert_eq!("0 0 0 15 * *", cron("FREQ=YEARLY;BYMONTHDAY=15"));
+    assert_eq!(
+        "0 0 0 15 Jun *",
+        cron("FREQ=YEARLY;BYMONTH=6;BYMONTHDAY=15")
+    );
+}
+
+#[test]
+fn intervals_step_through_the_frequency() {
+    assert_eq!("0 */15 * * * *", cron("FREQ=MINUTELY;INTERVAL=15"));
+    assert_eq!("0 0 */6 * * *", cron("FREQ=HOURLY;INTERVAL=6"));
+    assert_eq!("0 0 0 1 */3 *", cron("FREQ=MONTHLY;INTERVAL=3"));
+    assert_eq!("0 0 0 * * *", cron("FREQ=DAILY;INTERVAL=1"));
+}
+
+#[test]
+fn days_count_from_the_end_of_the_month() {
+    assert_eq!("0 0 0 L,L-2 * *", cron("FREQ=MONTHLY;BYMONTHDAY=-1,-3"));
+    assert_eq!("0 0 0 * * FriL,Mon#2", cron("FREQ=MONTHLY;BYDAY=2MO,-1FR"));
+    assert_eq!(
+        "0 0 0 * Nov Thu#4",
+        cron("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH")
+    );
+}
+
+#[test]
+fn turns_down_what_a_schedule_cant_follow() {
+    let unsupported = |rule: &str, token: &str| {
+        let error = Schedule::from_rrule(rule).unwrap_err();
+        let start = rule.find(token).unwrap();
+        assert_eq!(
+            Error::Unsupported {
+                token: token.to_owned(),
+                span: start..start + token.len(),
+            },
+            error,
+            "{rule}"
+        );
+    };
+    unsupported("FREQ=DAILY;COUNT=10", "COUNT=10");
+    unsupported(
+        "FREQ=DAILY;UNTIL=20230601T000000Z",
+        "UNTIL=20230601T000000Z",
+    );
+    unsupported("FREQ=MONTHLY;BYDAY=MO;BYSETPOS=1", "BYSETPOS=1");
+    unsupported("FREQ=DAILY;INTERVAL=2", "INTERVAL=2");
+    unsupported("FREQ=MINUTELY;INTERVAL=7", "INTERVAL=7");
+    unsupported("FREQ=MINUTELY;INTERVAL=15;BYMINUTE=5", "INTERVAL=15");
+    unsupported("FREQ=MONTHLY;BYMONTHDAY=1;BYDAY=MO", "BYDAY=MO");
+    unsupported("FREQ=WEEKLY;BYMONTHDAY=1", "BYMONTHDAY=1");
+    unsupported("FREQ=WEEKLY;BYDAY=2MO", "BYDAY=2MO");
+    unsupported("FREQ=MONTHLY;BYDAY=-2MO", "BYDAY=-2MO");
+}
+
+#[test]
+fn points_at_parts_that_dont_parse() {
+    let span = |rule: &str| Schedule::from_rrule(rule).unwrap_err().span();
+    assert_eq!(Some(11..16), span("FREQ=DAILY;BOGUS"));
+    assert_eq!(Some(5..11), span("FREQ=HOURLI;BYHOUR=1"));
+    assert_eq!(Some(21..22), span("FREQ=HOURLY;INTERVAL=0"));
+    assert_eq!(Some(26..28), span("RRULE:FREQ=DAILY;BYHOUR=1,24"));
+    assert_eq!(Some(21..23), span("FREQ=WEEKLY;BYDAY=MO,XX"));
+    assert_eq!(Some(8..8), span("BYHOUR=1"));
+    assert_eq!(
+        "missing FREQ at column 9",
+        Schedule::from_rrule("BYHOUR=1").unwrap_err().to_string()
+    );
+    assert_eq!(Some(Error::Empty), Schedule::from_rrule("RRULE:").err());
+}
+
+#[test]
+fn writes_tables_out_as_rules() {
+    assert_eq!(
+        Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=13,14,15,16,17,18;BYMINUTE=16;BYSECOND=0"),
+        rrule("0 16 13-18 * * Mon-Fri").as_deref()
+    );
+    assert_eq!(
+        Some("FREQ=DAILY;BYHOUR=0;BYMINUTE=0;BYSECOND=0"),
+        rrule("@daily").as_deref()
+    );
+    assert_eq!(
+        Some("FREQ=MONTHLY;BYMONTH=1,7;BYMONTHDAY=1,-1;BYHOUR=9;BYMINUTE=30;BYSECOND=0"),
+        rrule("0 30 9 1,L Jan,Jul *").as_deref()
+    );
+    assert_eq!(
+        Some("FREQ=MONTHLY;BYDAY=2MO,-1FR;BYHOUR=0;BYMINUTE=0;BYSECOND=0"),
+        rrule("0 0 0 * * Mon#2,5L").as_deref()
+    );
+    assert_eq!(None, rrule("0 0 0 * * * 2024"));
+    assert_eq!(None, rrule("0 0 0 1 * Mon"));
+    assert_eq!(None, rrule("0 0 0 15W * *"));
+    assert_eq!(None, rrule("@every 90m"));
+}
+
+#[test]
+fn rules_round_trip() {
+    for expression in [
+        "0 16 13-18 * * Mon-Fri",
+        "0 */15 * * * *",
+        "30 0 9 1,15 Jan,Jul *",
+        "0 0 0 L-2 * *",
+        "0 0 12 * Nov 4#4",
+        "* * * * * *",
+    ] {
+        let schedule: Schedule = expression.parse().unwrap();
+        let rule = schedule.to_rrule().unwrap();
+        let back = Schedule::from_rrule(&rule).unwrap();
+        assert!(schedule.equivalent(&back), "{expression} -> {rule}");
+    }
+}
+
+#[test]
+fn writes_a_calendar_that_repeats_with_the_rule() {
+    let schedule: Schedule = "0 16 13-18 * * Mon-Fri".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let ics = schedule.to_ics(&start, 10);
+    let lines: Vec<&str> = ics.split_terminator("\r\n").collect();
+    assert_eq!(["BEGIN:VCALENDAR", "VERSION:2.0"], lines[..2]);
+    assert_eq!(Some(&"END:VCALENDAR"), lines.last());
+    assert!(lines.contains(&"DTSTART:20230601T131600Z"));
+    assert!(lines.iter().all(|line| line.len() <= 75));
+
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(
+        "\r\nRRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=13,14,15,16,17,18;BYMINUTE=16;BYSECOND=0;COUNT=10\r\n"
+    ));
+    assert!(unfolded.contains("\r\nSUMMARY:At 13:16:00\\, 14:16:00\\, "));
+    assert!(!unfolded.contains("RDATE"));
+}
+
+#[test]
+fn lists_out_times_that_a_rule_cant_repeat() {
+    let schedule: Schedule = "0 0 9 1 * Mon".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let ics = schedule.to_ics(&start, 3);
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains("DTSTART:20230601T090000Z\r\n"));
+    assert!(unfolded.contains("RDATE:20230605T090000Z,20230612T090000Z\r\n"));
+    assert!(unfolded.contains("SUMMARY:"));
+    assert!(!unfolded.contains("RRULE"));
+
+    let empty = "0 0 0 1 1 * 2020"
+        .parse::<Schedule>()
+        .unwrap()
+        .to_ics(&start, 3);
+    assert_eq!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//cron//Schedule//EN\r\nEND:VCALENDAR\r\n",
+        empty
+    );
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn local_times_get_listed_out_in_utc() {
+    let schedule: Schedule = "CRON_TZ=Europe/Berlin 0 0 9 * * *".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let unfolded = schedule.to_ics(&start, 2).replace("\r\n ", "");
+    assert!(unfolded.contains("DTSTART:20230601T070000Z\r\n"));
+    assert!(unfolded.contains("RDATE:20230602T070000Z\r\n"));
+    assert!(!unfolded.contains("RRULE"));
+}
+
+#[test]
+fn uids_stay_the_same_for_the_same_event() {
+    let schedule: Schedule = "0 16 13-18 * * Mon-Fri".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+    let uid = |ics: String| {
+        ics.lines()
+            .find_map(|line| line.strip_prefix("UID:").map(str::to_owned))
+            .unwrap()
+    };
+    // Pinned, so that a change to the hash can't quietly
+    // turn every exported event into a new one.
+    assert_eq!(
+        "20230601T131600Z-c39c6f5c6b6ca274@cron",
+        uid(schedule.to_ics(&start, 10))
+    );
+    assert_ne!(
+        uid(schedule.to_ics(&start, 10)),
+        uid(schedule.to_ics(&start, 5))
+    );
+}
@@ -0,0 +1,298 @@
+use super::{iterator::Direction, Calendar, Schedule, Timing};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use std::ops::RangeInclusive;
+
+/// How far a `ScheduleSet` looks for a datetime that gets past
+/// its intersections and exclusions before giving up, the same
+/// as a single schedule does.
+const SEARCH_DAYS: i64 = 400 * 366;
+
+/// How many datetimes a `ScheduleSet` tries, one after another,
+/// before giving up. Exclusions get stepped past one datetime at
+/// a time, so without this a set that runs every second, and
+/// leaves out every second, would take days to find out.
+const SEARCH_STEPS: u32 = 100_000;
+
+/// Several schedules combined into one stream of datetimes.
+///
+/// A set runs whenever any of the schedules added with `or`
+/// runs, as long as every schedule added with `and` runs at
+/// the same moment, and none of the ones added with `except` do.
+/// Whole days can be left out with `except_on` or a `Calendar`
+/// passed to `except_calendar`. Datetimes that
+/// more than one schedule lands on only come out once.
+///
+/// Intersections and exclusions are checked with
+/// `Schedule::matches`, so an `@every` schedule never
+/// matches anything there.
+#[derive(Clone, Debug, Default)]
+pub struct ScheduleSet {
+    schedules: Vec<Schedule>,
+    required: Vec<Schedule>,
+    excluded: Vec<Schedule>,
+    calendar: Calendar,
+}
+
+impl ScheduleSet {
+    /// Creates a set that never runs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also runs whenever `schedule` does.
+    pub fn or(mut self, schedule: Schedule) -> Self {
+        self.schedules.push(schedule);
+        self
+    }
+
+    /// Only runs when `schedule` runs at the same moment.
+    pub fn and(mut self, schedule: Schedule) -> Self {
+        self.required.push(schedule);
+        self
+    }
+
+    /// Never runs when `schedule` runs at the same moment.
+    /// Left out datetimes get stepped past one at a time, so
+    /// more than 100,000 of them in a row ends the set there.
+    pub fn except(mut self, schedule: Schedule) -> Self {
+        self.excluded.push(schedule);
+        self
+    }
+
+    /// Never runs on any of `dates`. The dates are checked in
+    /// whichever timezone the set is being iterated in.
+    pub fn except_on<I: IntoIterator<Item = NaiveDate>>(mut self, dates: I) -> Self {
+        self.calendar.extend(dates);
+        self
+    }
+
+    /// Never runs on any of the days in `calendar`. Like with
+    /// `except_on`, the days are checked in whichever timezone
+    /// the set is being iterated in.
+    pub fn except_calendar(mut self, calendar: &Calendar) -> Self {
+        self.calendar.merge(calendar);
+        self
+    }
+
+    pub fn iter_with_timezone<Tz: TimeZone + 'static>(
+        &self,
+        tz: Tz,
+    ) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+        self.iter_from(&Utc::now().with_timezone(&tz))
+    }
+
+    pub fn into_iter_with_timezone<Tz: TimeZone + 'static>(
+        self,
+        tz: Tz,
+    ) -> impl Iterator<Item = DateTime<Tz>> {
+        self.into_iter_from(&Utc::now().with_timezone(&tz))
+    }
+
+    /// Iterates over the datetimes that come strictly
+    /// after `start`, in the same timezone as `start`.
+    pub fn iter_from<Tz: TimeZone + 'static>(
+        &self,
+        start: &DateTime<Tz>,
+    ) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+        std::iter::successors(self.after(start), |datetime| self.after(datetime))
+    }
+
+    /// The owned version of `iter_from`.
+    pub fn into_iter_from<Tz: TimeZone + 'static>(
+        self,
+        start: &DateTime<Tz>,
+    ) -> impl Iterator<Item = DateTime<Tz>> {
+        std::iter::successors(self.after(start), move |datetime| self.after(datetime))
+    }
+
+    /// Iterates over the datetimes that come strictly before
+    /// `start`, walking back into the past from the most recent one.
+    pub fn iter_backwards_from<Tz: TimeZone + 'static>(
+        &self,
+        start: &DateTime<Tz>,
+    ) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+        std::iter::successors(self.before(start), |datetime| self.before(datetime))
+    }
+
+    /// Returns the first datetime that the set runs at,
+    /// strictly after `datetime`.
+    pub fn after<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        self.search(datetime, Direction::Forward)
+    }
+
+    /// Returns the last datetime that the set ran at,
+    /// strictly before `datetime`.
+    pub fn before<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        self.search(datetime, Direction::Backward)
+    }
+
+    /// Returns true if the set runs at `datetime`,
+    /// ignoring anything under a second.
+    pub fn matches<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> bool {
+        self.schedules
+            .iter()
+            .any(|schedule| schedule.matches(datetime))
+            && self
+                .required
+                .iter()
+                .all(|schedule| schedule.matches(datetime))
+            && !self.is_excluded(datetime)
+    }
+
+    fn is_excluded<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> bool {
+        self.calendar.contains(datetime.date_naive())
+            || self
+                .excluded
+                .iter()
+                .any(|schedule| schedule.matches(datetime))
+    }
+
+    /// Walks from `datetime` until something runs at a moment
+    /// that the intersections and exclusions let through.
+    ///
+    /// When an intersected schedule doesn't match, the search
+    /// jumps ahead to that schedule's next datetime instead
+    /// of going through the others one at a time. Exclusions
+    /// can't be jumped over like that, so the search gives up
+    /// after `SEARCH_STEPS` tries.
+    fn search<Tz: TimeZone + 'static>(
+        &self,
+        datetime: &DateTime<Tz>,
+        direction: Direction,
+    ) -> Option<DateTime<Tz>> {
+        if self
+            .required
+            .iter()
+            .any(|schedule| matches!(schedule.timing, Timing::Every(_)))
+        {
+            return None;
+        }
+
+        let mut candidate = self.step(datetime, direction)?;
+        for _ in 0..SEARCH_STEPS {
+            if (candidate.clone() - datetime.clone()).num_days().abs() > SEARCH_DAYS {
+                return None;
+            }
+            if let Some(schedule) = self
+                .required
+                .iter()
+                .find(|schedule| !schedule.matches(&candidate))
+            {
+                let next = direction.step(schedule, &candidate)?;
+                candidate = self.step_to(&next, direction)?;
+            } else if let Some(days) = self.calendar.range_containing(candidate.date_naive()) {
+                candidate = match past_days(days, direction, &candidate.timezone()) {
+                    Some(boundary) => self.step_to(&boundary, direction)?,
+                    None => self.step(&candidate, direction)?,
+                };
+            } else if self.is_excluded(&candidate) {
+                candidate = self.step(&candidate, direction)?;
+            } else {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
+    /// Returns the nearest datetime that any of the
+    /// schedules runs at, going `direction` from `datetime`.
+    fn step<Tz: TimeZone + 'static>(
+        &self,
+        datetime: &DateTime<Tz>,
+        direction: Direction,
+    ) -> Option<DateTime<Tz>> {
+        self.schedules
+            .iter()
+            .filter_map(|schedule| direction.step(schedule, datetime))
+            .reduce(|a, b| direction.nearest(a, b))
+    }
+
+    /// Same as `step`, but `datetime` itself counts.
+    fn step_to<Tz: TimeZone + 'static>(
+        &self,
+        datetime: &DateTime<Tz>,
+        direction: Direction,
+    ) -> Option<DateTime<Tz>> {
+        if self
+            .schedules
+            .iter()
+            .any(|schedule| schedule.matches(datetime))
+        {
+            // `matches` ignores anything under a second.
+            let nanos = Duration::nanoseconds(datetime.timestamp_subsec_nanos().into());
+            Some(datetime.clone() - nanos)
+        } else {
+            self.step(datetime, direction)
+        }
+    }
+}
+
+/// Returns the first moment outside of `days`, going `direction`,
+/// so that a search can skip over a long break in one go.
+/// Gives back `None` when that moment is hard to pin down,
+/// like when the clocks change right at midnight.
+fn past_days<Tz: TimeZone>(
+    days: &RangeInclusive<NaiveDate>,
+    direction: Direction,
+    tz: &Tz,
+) -> Option<DateTime<Tz>> {
+    match direction {
+        Direction::Forward => {
+            let local = days.end().succ_opt()?.and_time(NaiveTime::MIN);
+            tz.from_local_datetime(&local).earliest()
+        }
+        Direction::Backward => {
+            let local = days.start().pred_opt()?.and_hms_opt(23, 59, 59)?;
+            tz.from_local_datetime(&local).latest()
+        }
+    }
+}
+
+/// Anything that fires at a stream of datetimes, so code
+/// that runs things on a schedule can take either a single
+/// `Schedule` or a whole `ScheduleSet`.
+pub trait Occurrences {
+    /// Returns the first datetime strictly after `datetime`.
+    fn after<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>>;
+
+    /// Returns the last datetime strictly before `datetime`.
+    fn before<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>>;
+}
+
+impl Occurrences for Schedule {
+    fn after<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        Schedule::after(self, datetime)
+    }
+
+    fn before<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        Schedule::before(self, datetime)
+    }
+}
+
+impl Occurrences for ScheduleSet {
+    fn after<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        ScheduleSet::after(self, datetime)
+    }
+
+    fn before<Tz: TimeZone + 'static>(&self, datetime: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        ScheduleSet::before(self, datetime)
+    }
+}
+
+impl From<Schedule> for ScheduleSet {
+    fn from(schedule: Schedule) -> Self {
+        Self::new().or(schedule)
+    }
+}
+
+impl FromIterator<Schedule> for ScheduleSet {
+    fn from_iter<I: IntoIterator<Item = Schedule>>(schedules: I) -> Self {
+        Self {
+            schedules: schedules.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,176 @@
+use super::ScheduleSet;
+use crate::schedule::Schedule;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc, Weekday};
+use chrono_tz::America::Chicago;
+
+fn schedule(expression: &str) -> Schedule {
+    expression.parse().unwrap()
+}
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, min, 0)
+        .unwrap()
+}
+
+#[test]
+fn union_merges_in_order() {
+    let set = ScheduleSet::from(schedule("16 13 * * Mon-Fri")).or(schedule("16 10 * * Sat"));
+    // Thursday, June 1st 2023
+    let start = utc(2023, 6, 1, 0, 0);
+
+    let datetimes: Vec<_> = set.iter_from(&start).take(6).collect();
+    assert_eq!(
+        vec![
+            utc(2023, 6, 1, 13, 16),
+            utc(2023, 6, 2, 13, 16),
+            utc(2023, 6, 3, 10, 16),
+            utc(2023, 6, 5, 13, 16),
+            utc(2023, 6, 6, 13, 16),
+            utc(2023, 6, 7, 13, 16),
+        ],
+        datetimes
+    );
+}
+
+#[test]
+fn union_removes_duplicates() {
+    let set: ScheduleSet = [schedule("*/15 * * * *"), schedule("*/10 * * * *")]
+        .into_iter()
+        .collect();
+    let start = utc(2023, 6, 1, 0, 0);
+
+    let minutes: Vec<_> = set
+        .iter_from(&start)
+        .take(7)
+        .map(|datetime| datetime.minute())
+        .collect();
+    assert_eq!(vec![10, 15, 20, 30, 40, 45, 50], minutes);
+}
+
+#[test]
+fn intersection_keeps_shared_datetimes() {
+    // Every 4th hour, but only the ones that are also on the 6th.
+    let set = ScheduleSet::from(schedule("0 */4 * * *")).and(schedule("0 */6 * * *"));
+    let start = utc(2023, 6, 1, 0, 0);
+
+    let hours: Vec<_> = set
+        .iter_from(&start)
+        .take(4)
+        .map(|datetime| datetime.hour())
+        .collect();
+    assert_eq!(vec![12, 0, 12, 0], hours);
+}
+
+#[test]
+fn intersection_jumps_ahead() {
+    // Friday the 13th, at noon.
+    let set = ScheduleSet::from(schedule("0 12 13 * *")).and(schedule("0 12 * * Fri"));
+    let start = utc(2023, 1, 1, 0, 0);
+
+    let datetimes: Vec<_> = set.iter_from(&start).take(3).collect();
+    assert_eq!(
+        vec![
+            utc(2023, 1, 13, 12, 0),
+            utc(2023, 10, 13, 12, 0),
+            utc(2024, 9, 13, 12, 0),
+        ],
+        datetimes
+    );
+}
+
+#[test]
+fn exclusions_leave_out_matching_datetimes() {
+    let spring_break = schedule("* * 13-17 Mar *");
+    let set = ScheduleSet::from(schedule("16 13 * * Mon-Fri")).except(spring_break);
+    let start = utc(2023, 3, 10, 0, 0);
+
+    let days: Vec<_> = set
+        .iter_from(&start)
+        .take(3)
+        .map(|datetime| datetime.day())
+        .collect();
+    assert_eq!(vec![10, 20, 21], days);
+}
+
+#[test]
+fn excluded_dates_use_the_local_date() {
+    let holidays = [
+        NaiveDate::from_ymd_opt(2023, 7, 4).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 12, 25).unwrap(),
+    ];
+    // 8 PM in Chicago is already the next day in UTC.
+    let set = ScheduleSet::from(schedule("0 20 * * *")).except_on(holidays);
+    let start = Chicago.with_ymd_and_hms(2023, 7, 3, 21, 0, 0).unwrap();
+
+    let next = set.after(&start).unwrap();
+    assert_eq!(
+        Chicago.with_ymd_and_hms(2023, 7, 5, 20, 0, 0).unwrap(),
+        next
+    );
+    assert!(!set.matches(&Chicago.with_ymd_and_hms(2023, 12, 25, 20, 0, 0).unwrap()));
+    assert!(set.matches(&Chicago.with_ymd_and_hms(2023, 12, 26, 20, 0, 0).unwrap()));
+}
+
+#[test]
+fn before_undoes_after() {
+    let set = ScheduleSet::from(schedule("16 13 * * Mon-Fri"))
+        .or(schedule("16 10 * * Sat"))
+        .and(schedule("* * * Jan-Nov *"))
+        .except_on([NaiveDate::from_ymd_opt(2023, 6, 6).unwrap()]);
+    let start = utc(2023, 5, 1, 0, 0);
+
+    let forward: Vec<_> = set.iter_from(&start).take(200).collect();
+    let mut backward: Vec<_> = set
+        .iter_backwards_from(&(*forward.last().unwrap() + Duration::seconds(1)))
+        .take(200)
+        .collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert!(forward.iter().all(|datetime| set.matches(datetime)));
+    assert!(forward.iter().all(|datetime| datetime.month() != 12));
+    assert!(forward
+        .iter()
+        .all(|datetime| datetime.weekday() != Weekday::Sun));
+}
+
+#[test]
+fn impossible_sets_end() {
+    let set = ScheduleSet::from(schedule("0 0 * * *")).and(schedule("0 12 * * *"));
+    assert_eq!(None, set.after(&utc(2023, 1, 1, 0, 0)));
+
+    let set = ScheduleSet::from(schedule("0 0 * * *")).and(schedule("@every 1h"));
+    assert_eq!(None, set.after(&utc(2023, 1, 1, 0, 0)));
+
+    assert_eq!(None, ScheduleSet::new().after(&utc(2023, 1, 1, 0, 0)));
+}
+
+#[test]
+fn excluding_everything_ends() {
+    let every_second = schedule("* * * * * *");
+    let set = ScheduleSet::from(every_second.clone()).except(every_second);
+    let started = std::time::Instant::now();
+
+    assert_eq!(None, set.after(&utc(2023, 1, 1, 0, 0)));
+    assert_eq!(None, set.before(&utc(2023, 1, 1, 0, 0)));
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+    // Most of a day of seconds still gets stepped through.
+    let set = ScheduleSet::from(schedule("* * * * * *")).except(schedule("* * 0-22 * * *"));
+    assert_eq!(
+        Some(utc(2023, 1, 1, 23, 0)),
+        set.after(&utc(2023, 1, 1, 0, 0))
+    );
+}
+
+#[test]
+fn owned_iter_with_timezone() {
+    let set = ScheduleSet::from(schedule("0 0 * * * *")).or(schedule("0 30 * * * *"));
+    let now = Utc::now();
+
+    let mut prev = now;
+    for datetime in set.into_iter_with_timezone(Utc).take(10) {
+        assert!(datetime > prev);
+        assert_eq!(0, datetime.minute() % 30);
+        prev = datetime;
+    }
+}
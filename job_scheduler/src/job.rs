@@ -1,7 +1,7 @@
 use self::job_internal::Job;
 use crate::{runner::RunningJobs, AsyncFn, JobId, Limit};
 use chrono::{DateTime, TimeZone, Utc};
-use cron::Schedule;
+use cron::schedule::Occurrences;
 use futures::future::BoxFuture;
 use std::{
     cmp::Reverse,
@@ -11,7 +11,7 @@ use std::{
 mod job_internal {
     use crate::{AsyncFn, JobId, Limit};
     use chrono::{DateTime, TimeZone, Utc};
-    use cron::Schedule;
+    use cron::schedule::Occurrences;
 
     /// The Job item itself. Contains the async function/closure
     /// and the schedule for when this job should be executed. Interprets
@@ -35,15 +35,22 @@ mod job_internal {
     {
         /// Creates a new job struct with the supplied
         /// id, ['job_scheduler::AsyncFn'], schedule, timezone,
-        /// and limit
-        pub fn with_limit<C: AsyncFn + Send + 'static>(
+        /// and limit. The schedule can be a single `cron::Schedule`
+        /// or a `cron::schedule::ScheduleSet`.
+        pub fn with_limit<C, S>(
             id: JobId,
             command: C,
-            schedule: Schedule,
+            schedule: S,
             timezone: T,
             limit: Limit,
-        ) -> Self {
-            let mut schedule = schedule.upcoming_owned(timezone);
+        ) -> Self
+        where
+            C: AsyncFn + Send + 'static,
+            S: Occurrences + Send + 'static,
+        {
+            let now = Utc::now().with_timezone(&timezone);
+            let mut schedule =
+                std::iter::successors(schedule.after(&now), move |last| schedule.after(last));
             Self {
                 id,
                 next_exec_time: schedule.next(),
@@ -51,7 +58,7 @@ mod job_internal {
                 schedule: match limit {
                     Limit::None => Box::new(schedule),
                     Limit::NumTimes(num_times) => {
-                        Box::new(schedule.take(num_times.checked_sub(1).unwrap_or_default()))
+                        Box::new(schedule.take(num_times.saturating_sub(1)))
                     }
                     Limit::EndDate(end_date) => Box::new(schedule.take_while(move |date_time| {
                         date_time.with_timezone(&Utc).timestamp()
@@ -94,6 +101,9 @@ mod job_internal {
         }
     }
 
+    // The heap orders jobs with `partial_cmp`, which breaks ties
+    // on the id, unlike `cmp`.
+    #[allow(clippy::non_canonical_partial_ord_impl)]
     impl<T> PartialOrd for Job<T>
     where
         T: TimeZone + Send,
@@ -162,7 +172,8 @@ mod job_internal {
     mod tests {
         use super::Job;
         use crate::Limit;
-        use chrono::Utc;
+        use chrono::{Timelike, Utc};
+        use cron::{schedule::ScheduleSet, Schedule};
 
         #[test]
         fn id_matches_what_was_given() {
@@ -171,7 +182,7 @@ mod job_internal {
             let job = Job::with_limit(
                 id,
                 || async { Ok(()) },
-                "00 * * * * *".parse().unwrap(),
+                "00 * * * * *".parse::<Schedule>().unwrap(),
                 Utc,
                 Limit::NumTimes(0),
             );
@@ -183,7 +194,7 @@ mod job_internal {
             let mut job1 = Job::with_limit(
                 1,
                 || async { Ok(()) },
-                "00 * * * * *".parse().unwrap(),
+                "00 * * * * *".parse::<Schedule>().unwrap(),
                 Utc,
                 Limit::NumTimes(0),
             );
@@ -191,7 +202,7 @@ mod job_internal {
             let job2 = Job::with_limit(
                 2,
                 || async { Ok(()) },
-                "00 * * * * *".parse().unwrap(),
+                "00 * * * * *".parse::<Schedule>().unwrap(),
                 Utc,
                 Limit::NumTimes(0),
             );
@@ -206,7 +217,7 @@ mod job_internal {
             let mut sooner_job = Job::with_limit(
                 1,
                 || async { Ok(()) },
-                "00 * * * * *".parse().unwrap(),
+                "00 * * * * *".parse::<Schedule>().unwrap(),
                 Utc,
                 Limit::NumTimes(3),
             );
@@ -214,7 +225,7 @@ mod job_internal {
             let mut later_job = Job::with_limit(
                 2,
                 || async { Ok(()) },
-                "00 * * * * *".parse().unwrap(),
+                "00 * * * * *".parse::<Schedule>().unwrap(),
                 Utc,
                 Limit::NumTimes(3),
             );
@@ -225,6 +236,26 @@ mod job_internal {
 
             assert!(sooner_job < later_job);
         }
+
+        #[test]
+        fn job_runs_whenever_any_schedule_in_a_set_does() {
+            let set = ScheduleSet::new()
+                .or("00 * * * * *".parse::<Schedule>().unwrap())
+                .or("30 * * * * *".parse::<Schedule>().unwrap());
+            let mut job = Job::with_limit(1, || async { Ok(()) }, set, Utc, Limit::NumTimes(4));
+
+            let mut times = vec![];
+            while let Some(time) = job.next_exec_time() {
+                times.push(*time);
+                job.advance_schedule();
+            }
+
+            assert_eq!(times.len(), 4);
+            assert!(times.iter().all(|time| time.second() % 30 == 0));
+            assert!(times
+                .windows(2)
+                .all(|pair| pair[1] - pair[0] == chrono::Duration::seconds(30)));
+        }
     }
 }
 
@@ -299,15 +330,16 @@ where
         }
     }
 
-    pub fn schedule_with_limit<C>(
+    pub fn schedule_with_limit<C, S>(
         &mut self,
         command: C,
-        schedule: Schedule,
+        schedule: S,
         timezone: T,
         limit: Limit,
     ) -> JobId
     where
         C: AsyncFn + Send + 'static,
+        S: Occurrences + Send + 'static,
     {
         if let Ok(running_jobs) = self.running_jobs.lock() {
             for (id, was_removed) in (0u32..).zip(self.scheduled_for_deletion.iter_mut()) {
@@ -46,6 +46,7 @@ mod receiver {
             Self { rx, peeked: None }
         }
 
+        #[allow(clippy::unnecessary_unwrap)]
         pub fn peek(&mut self) -> Result<&T, &mpsc::TryRecvError> {
             if self.peeked.is_some() {
                 self.peeked.as_ref().unwrap().as_ref()
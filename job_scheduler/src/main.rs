@@ -6,7 +6,7 @@ fn main() {
     env_logger::init();
     let mut s = Scheduler::with_timezone(Local);
 
-    let schedule = "30 * * * * *".parse().unwrap();
+    let schedule: cron::Schedule = "30 * * * * *".parse().unwrap();
 
     let _id = s.add_job(
         || async {
@@ -29,7 +29,7 @@ fn main() {
 
             Ok(())
         },
-        "45 * * * * *".parse().unwrap(),
+        "45 * * * * *".parse::<cron::Schedule>().unwrap(),
         job_scheduler::Limit::NumTimes(2),
     );
 
@@ -41,7 +41,7 @@ fn main() {
                 let _ = "5".parse::<i32>()?;
                 Ok(())
             },
-            "20 * * * * *".parse().unwrap(),
+            "20 * * * * *".parse::<cron::Schedule>().unwrap(),
             job_scheduler::Limit::NumTimes(2),
         ));
     }
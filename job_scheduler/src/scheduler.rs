@@ -34,7 +34,7 @@ use std::{
 ///         println!("Hello World!");
 ///         Ok(())
 ///     },
-///     "00 * * * * *".parse().unwrap(),
+///     "00 * * * * *".parse::<cron::Schedule>().unwrap(),
 ///     Limit::NumTimes(5),
 /// );
 ///
@@ -60,7 +60,7 @@ use std::{
 ///         *num += 1;
 ///         Ok(())
 ///     },
-///     "00 * * * * *".parse().unwrap(),
+///     "00 * * * * *".parse::<cron::Schedule>().unwrap(),
 ///     Limit::NumTimes(3),
 /// );
 ///
@@ -317,7 +317,8 @@ where
     /// information on what a `Job` function looks like.
     ///
     /// Along with the function, the user must also supply a `cron::Schedule`,
-    /// which can be parsed from a string using the `parse` method, and
+    /// which can be parsed from a string using the `parse` method, or a
+    /// `cron::schedule::ScheduleSet` that combines several of them, and
     /// a `job_scheduler::Limit` if the user desires to automatically stop
     /// scheduling this job at some point later-on.
     ///
@@ -326,14 +327,10 @@ where
     ///
     /// Returns a `JobId` which is just a `u32` for the job just submitted.
     /// This can be used later to remove the job manually if desired.
-    pub fn add_job<C>(
-        &mut self,
-        command: C,
-        schedule: cron::Schedule,
-        limit_num_execs: crate::Limit,
-    ) -> JobId
+    pub fn add_job<C, S>(&mut self, command: C, schedule: S, limit_num_execs: crate::Limit) -> JobId
     where
         C: AsyncFn + Send + 'static,
+        S: cron::schedule::Occurrences + Send + 'static,
     {
         let (job_id, should_stop_service) = match self.job_board.lock() {
             Ok(mut jobs) => (